@@ -1,4 +1,4 @@
-use clap::{CommandFactory, Parser, Subcommand};
+use clap::{Args, CommandFactory, Parser, Subcommand, ValueEnum};
 use clap_complete::{Shell, generate};
 use nssa::{
     AccountId, PublicTransaction,
@@ -6,12 +6,31 @@ use nssa::{
     public_transaction::{Message, WitnessSet},
 };
 use multisig_core::{
-    Instruction,
+    ConfigAction, ExpectedAccount, Instruction, MAX_MEMO_LEN, NESTED_VOTE_VOTER_INDEX, PostCondition,
+    ProposalStatus,
     compute_multisig_state_pda,
     compute_proposal_pda,
+    compute_vault_pda,
+    derive_create_key,
+    nested_vote_target_account_ids,
+    post_condition_account_indices,
+    vault_pda_seed_bytes,
 };
+use token_core::Instruction as TokenInstruction;
+use serde_json::{Value, json};
+use nssa_core::account::AccountWithMetadata;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::panic::AssertUnwindSafe;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::sync::Arc;
+use common::sequencer_client::SequencerClient;
 use wallet::WalletCore;
 
+#[path = "multisig/tui.rs"]
+mod tui;
+
 /// LEZ Multisig CLI — M-of-N threshold governance for LEZ
 ///
 /// Squads-style on-chain proposal flow:
@@ -20,14 +39,133 @@ use wallet::WalletCore;
 #[command(name = "multisig", version, about, long_about = None)]
 #[command(propagate_version = true)]
 struct Cli {
-    /// Path to the multisig program binary
-    #[arg(long, short = 'p', env = "MULTISIG_PROGRAM", default_value = "target/riscv32im-risc0-zkvm-elf/docker/multisig.bin")]
-    program: String,
+    /// Path to the multisig program binary; by default the program ID saved
+    /// with the multisig is used, or else target/riscv32im-risc0-zkvm-elf/docker/multisig.bin
+    #[arg(long, short = 'p', env = "MULTISIG_PROGRAM")]
+    program: Option<String>,
+
+    /// Config file with saved multisigs [default: ~/.config/multisig/config.toml]
+    #[arg(long, global = true, env = "MULTISIG_CONFIG")]
+    config: Option<String>,
+
+    /// Output format: human-readable text, or one JSON object on stdout
+    #[arg(long, global = true, value_enum, default_value_t = OutputFormat::Text)]
+    output: OutputFormat,
+
+    /// Instead of signing and submitting, write the member-signed transaction
+    /// to this file, to be signed offline with `sign` and sent with `submit`
+    #[arg(long, global = true)]
+    unsigned_out: Option<String>,
+
+    /// Run the instruction locally against the current on-chain accounts and
+    /// report the outcome, without submitting anything
+    #[arg(long, global = true, conflicts_with = "unsigned_out")]
+    dry_run: bool,
 
     #[command(subcommand)]
     command: Commands,
 }
 
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum OutputFormat {
+    Text,
+    Json,
+    /// Print nothing; used while the TUI owns the terminal
+    #[value(skip)]
+    Silent,
+}
+
+/// The multisig a command acts on: a name saved in the config file, or a create key.
+#[derive(Args)]
+struct MultisigArg {
+    /// Name of a multisig saved with `config add` (or `create --save-as`)
+    #[arg(value_name = "NAME", required_unless_present = "multisig", conflicts_with = "multisig")]
+    name: Option<String>,
+    /// Multisig create_key (base58), or a saved name
+    #[arg(long)]
+    multisig: Option<String>,
+}
+
+impl MultisigArg {
+    fn as_str(&self) -> &str {
+        self.name.as_deref().or(self.multisig.as_deref()).unwrap()
+    }
+}
+
+/// Target accounts as the executor expects them after the proposal's
+/// ChainedCall, for proposals with post-conditions. Accounts not given here
+/// are expected to keep their current state.
+#[derive(Args, Default)]
+struct PostStateArgs {
+    /// Native balance of target account INDEX after the call, as INDEX:AMOUNT (repeatable)
+    #[arg(long)]
+    post_balance: Vec<String>,
+    /// Token holding balance of target account INDEX after the call, as INDEX:AMOUNT (repeatable)
+    #[arg(long)]
+    post_token_balance: Vec<String>,
+}
+
+impl std::fmt::Display for MultisigArg {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Subcommand)]
+enum ConfigCommand {
+    /// Save a multisig under a name
+    Add {
+        /// Name to refer to the multisig by
+        name: String,
+        /// Multisig create_key (base58)
+        #[arg(long)]
+        create_key: String,
+        /// Multisig program ID (hex); used instead of reading --program
+        #[arg(long)]
+        program_id: Option<String>,
+        /// Token program ID (hex): decodes token calls in proposals and is the
+        /// default for `transfer`
+        #[arg(long)]
+        token_program_id: Option<String>,
+        /// Default member account ID (base58) for --account
+        #[arg(long)]
+        account: Option<String>,
+        /// Sequencer URL, instead of the wallet's
+        #[arg(long)]
+        sequencer_url: Option<String>,
+    },
+    /// Forget a saved multisig
+    Remove {
+        /// Saved name
+        name: String,
+    },
+    /// List saved multisigs
+    List,
+}
+
+#[derive(Subcommand)]
+enum ProposalCommand {
+    /// List all proposals of a multisig with status and vote tally
+    List {
+        #[command(flatten)]
+        multisig: MultisigArg,
+        /// Only show proposals still open (Active or Draft)
+        #[arg(long)]
+        pending: bool,
+    },
+    /// Show one proposal in full: votes, action and decoded instruction
+    Show {
+        #[command(flatten)]
+        multisig: MultisigArg,
+        /// Proposal index
+        #[arg(long, short = 'i')]
+        index: u64,
+        /// Also print the proposal account's raw bytes
+        #[arg(long)]
+        raw: bool,
+    },
+}
+
 #[derive(Subcommand)]
 enum Commands {
     /// Create a new M-of-N multisig
@@ -38,119 +176,390 @@ enum Commands {
         /// Member account IDs (base58)
         #[arg(long, short = 'm', num_args = 1..)]
         member: Vec<String>,
-        /// Optional create key (base58). If omitted, a random one is generated.
-        #[arg(long)]
+        /// Optional create key (base58). If omitted, a random one is generated
+        /// unless --label is given.
+        #[arg(long, conflicts_with = "label")]
         create_key: Option<String>,
+        /// Derive the create key from --creator, this label and a nonce, so the
+        /// multisig can later be found again with `recover`
+        #[arg(long, requires = "creator")]
+        label: Option<String>,
+        /// Creator account ID (base58) used for key derivation
+        #[arg(long, requires = "label")]
+        creator: Option<String>,
+        /// Derivation nonce. If omitted, the first nonce (below 64) without an existing multisig is used.
+        #[arg(long, requires = "label")]
+        nonce: Option<u64>,
+        /// Save the new multisig in the config file under this name
+        #[arg(long)]
+        save_as: Option<String>,
+    },
+
+    /// Find multisigs whose create keys were derived from --creator and --label
+    Recover {
+        /// Creator account ID (base58) used at creation
+        #[arg(long)]
+        creator: String,
+        /// Label used at creation
+        #[arg(long)]
+        label: String,
+        /// Number of nonces to scan, starting at 0
+        #[arg(long, default_value_t = 16)]
+        scan: u64,
     },
 
     /// Create a proposal (raw instruction data)
     Propose {
-        /// Multisig create_key (base58) to identify which multisig
-        #[arg(long)]
-        multisig: String,
-        /// Your account ID (base58, must be a member)
+        #[command(flatten)]
+        multisig: MultisigArg,
+        /// Your account ID (base58, must be a member);
+        /// defaults to the account saved with the multisig
         #[arg(long)]
-        account: String,
+        account: Option<String>,
         /// Target program ID (base58)
         #[arg(long)]
         target_program: String,
         /// Serialized instruction data for the target program (hex-encoded u32 words, e.g. "01000000 02000000")
         #[arg(long, num_args = 0..)]
         instruction_data: Vec<String>,
-        /// Number of target accounts expected at execute time
-        #[arg(long, default_value = "0")]
-        target_account_count: u8,
-        /// PDA seeds (hex-encoded 32-byte values)
+        /// Target account IDs (base58, in order) the proposal commits to — these exact
+        /// accounts must be passed at execute time
+        #[arg(long, num_args = 0..)]
+        target_account: Vec<String>,
+        /// PDA seeds (hex-encoded 32-byte values); the program only accepts this
+        /// multisig's vault seed
         #[arg(long, num_args = 0..)]
         pda_seed: Vec<String>,
         /// Which target account indices (0-based) get is_authorized=true
         #[arg(long, num_args = 0..)]
         authorized_index: Vec<u8>,
-        /// Proposal index hint (used to compute proposal PDA — set to expected next index)
+        /// Proposal title/description shown to members before they vote
+        #[arg(long, default_value = "")]
+        memo: String,
+        /// Hash of an off-chain document backing the proposal (hex, 32 bytes)
+        #[arg(long)]
+        content_hash: Option<String>,
+        /// Create as a draft for review; voting opens after `activate`
+        #[arg(long)]
+        draft: bool,
+        /// Index of an earlier proposal that must be executed before this one
+        #[arg(long)]
+        depends_on: Option<u64>,
+        /// Make the proposal recurring: seconds between runs
+        #[arg(long, requires_all = ["runs", "clock"])]
+        every: Option<u64>,
+        /// Number of runs of a recurring proposal
+        #[arg(long, requires = "every")]
+        runs: Option<u32>,
+        /// Deadline (clock time): the proposal cannot execute from then on
+        #[arg(long, requires = "clock")]
+        expires_at: Option<u64>,
+        /// Clock account ID (base58) providing the time for a recurring or expiring proposal
+        #[arg(long)]
+        clock: Option<String>,
+        /// Post-condition "INDEX:AMOUNT": target account INDEX's native balance may drop by at most AMOUNT
+        #[arg(long, num_args = 0..)]
+        max_decrease: Vec<String>,
+        /// Post-condition "INDEX:AMOUNT": target token holding INDEX's balance may drop by at most AMOUNT
+        #[arg(long, num_args = 0..)]
+        max_token_decrease: Vec<String>,
+        /// Post-condition: target account INDEX must exist after execution
+        #[arg(long, num_args = 0..)]
+        must_exist: Vec<u8>,
+    },
+
+    /// Propose a token transfer out of the multisig vault
+    Transfer {
+        #[command(flatten)]
+        multisig: MultisigArg,
+        /// Your account ID (base58, must be a member);
+        /// defaults to the account saved with the multisig
+        #[arg(long)]
+        account: Option<String>,
+        /// Token holding to pay from (base58); defaults to the multisig's vault PDA,
+        /// the only account the multisig can authorize
+        #[arg(long)]
+        vault: Option<String>,
+        /// Recipient token holding (base58)
+        #[arg(long)]
+        to: String,
+        /// Amount of tokens to transfer
+        #[arg(long)]
+        amount: u128,
+        /// Path to the token program binary; defaults to the token program
+        /// saved with the multisig
+        #[arg(long)]
+        token_program: Option<String>,
+        /// Token program ID (hex), instead of --token-program
+        #[arg(long, conflicts_with = "token_program")]
+        token_program_id: Option<String>,
+        /// Add a post-condition that the vault loses at most <amount> tokens. The
+        /// executor must then pass the vault's token balance after the transfer,
+        /// e.g. `execute --post-token-balance 0:<BALANCE>`
+        #[arg(long)]
+        cap_vault_decrease: bool,
+        /// Proposal title/description; defaults to "Transfer <amount> to <to>"
+        #[arg(long)]
+        memo: Option<String>,
+        /// Hash of an off-chain document backing the proposal (hex, 32 bytes)
         #[arg(long)]
-        proposal_index: u64,
+        content_hash: Option<String>,
+    },
+
+    /// Open a draft proposal for voting (proposer only)
+    Activate {
+        #[command(flatten)]
+        multisig: MultisigArg,
+        /// Proposal index
+        #[arg(long, short = 'i')]
+        index: u64,
+        /// Your account ID (base58, must be the proposer);
+        /// defaults to the account saved with the multisig
+        #[arg(long)]
+        account: Option<String>,
+    },
+
+    /// Mark a proposal Expired once its deadline has passed (anyone can send it)
+    Expire {
+        #[command(flatten)]
+        multisig: MultisigArg,
+        /// Proposal index
+        #[arg(long, short = 'i')]
+        index: u64,
+    },
+
+    /// List or show proposals, decoded for review
+    #[command(alias = "proposals", subcommand)]
+    Proposal(ProposalCommand),
+
+    /// Print a proposal account's raw bytes and decoded contents for review
+    /// (same as `proposal show --raw`)
+    Inspect {
+        #[command(flatten)]
+        multisig: MultisigArg,
+        /// Proposal index
+        #[arg(long, short = 'i')]
+        index: u64,
     },
 
     /// Approve a proposal
     Approve {
-        /// Multisig create_key (base58)
-        #[arg(long)]
-        multisig: String,
+        #[command(flatten)]
+        multisig: MultisigArg,
         /// Proposal index
         #[arg(long, short = 'i')]
         index: u64,
-        /// Your account ID (base58, must be a member)
+        /// Your account ID (base58, must be a member);
+        /// defaults to the account saved with the multisig
+        #[arg(long)]
+        account: Option<String>,
+        /// Also execute the proposal in the same transaction if this approval reaches threshold
         #[arg(long)]
-        account: String,
+        execute: bool,
+        #[command(flatten)]
+        post_states: PostStateArgs,
     },
 
     /// Reject a proposal
     Reject {
-        /// Multisig create_key (base58)
-        #[arg(long)]
-        multisig: String,
+        #[command(flatten)]
+        multisig: MultisigArg,
         /// Proposal index
         #[arg(long, short = 'i')]
         index: u64,
-        /// Your account ID (base58, must be a member)
+        /// Your account ID (base58, must be a member);
+        /// defaults to the account saved with the multisig
         #[arg(long)]
-        account: String,
+        account: Option<String>,
     },
 
     /// Execute a fully-approved proposal
     Execute {
-        /// Multisig create_key (base58)
-        #[arg(long)]
-        multisig: String,
+        #[command(flatten)]
+        multisig: MultisigArg,
         /// Proposal index
         #[arg(long, short = 'i')]
         index: u64,
-        /// Your account ID (base58, must be a member)
+        /// Your account ID (base58, must be a member unless permissionless execution is enabled);
+        /// defaults to the account saved with the multisig
         #[arg(long)]
-        account: String,
+        account: Option<String>,
+        /// Target account IDs (base58, in order) for the ChainedCall; defaults to
+        /// the accounts recorded in the proposal
+        #[arg(long, num_args = 0..)]
+        target_account: Vec<String>,
+        #[command(flatten)]
+        post_states: PostStateArgs,
     },
 
     /// Propose adding a new member to the multisig
     AddMember {
-        /// Multisig create_key (base58)
+        #[command(flatten)]
+        multisig: MultisigArg,
+        /// Your account ID (base58, must be a member);
+        /// defaults to the account saved with the multisig
         #[arg(long)]
-        multisig: String,
-        /// Your account ID (base58, must be a member)
-        #[arg(long)]
-        account: String,
+        account: Option<String>,
         /// New member account ID (base58)
         #[arg(long)]
         member: String,
+        /// Proposal title/description shown to members before they vote
+        #[arg(long, default_value = "")]
+        memo: String,
+        /// Hash of an off-chain document backing the proposal (hex, 32 bytes)
+        #[arg(long)]
+        content_hash: Option<String>,
     },
 
     /// Propose removing a member from the multisig
     RemoveMember {
-        /// Multisig create_key (base58)
-        #[arg(long)]
-        multisig: String,
-        /// Your account ID (base58, must be a member)
+        #[command(flatten)]
+        multisig: MultisigArg,
+        /// Your account ID (base58, must be a member);
+        /// defaults to the account saved with the multisig
         #[arg(long)]
-        account: String,
+        account: Option<String>,
         /// Member to remove (base58)
         #[arg(long)]
         member: String,
+        /// Proposal title/description shown to members before they vote
+        #[arg(long, default_value = "")]
+        memo: String,
+        /// Hash of an off-chain document backing the proposal (hex, 32 bytes)
+        #[arg(long)]
+        content_hash: Option<String>,
     },
 
     /// Propose changing the approval threshold
     ChangeThreshold {
-        /// Multisig create_key (base58)
-        #[arg(long)]
-        multisig: String,
-        /// Your account ID (base58, must be a member)
+        #[command(flatten)]
+        multisig: MultisigArg,
+        /// Your account ID (base58, must be a member);
+        /// defaults to the account saved with the multisig
         #[arg(long)]
-        account: String,
+        account: Option<String>,
         /// New threshold value
         #[arg(long)]
         threshold: u8,
+        /// Proposal title/description shown to members before they vote
+        #[arg(long, default_value = "")]
+        memo: String,
+        /// Hash of an off-chain document backing the proposal (hex, 32 bytes)
+        #[arg(long)]
+        content_hash: Option<String>,
+    },
+
+    /// Propose (in this multisig) a vote on a proposal of a parent multisig that has
+    /// this multisig's vault as a member
+    ProposeNestedVote {
+        #[command(flatten)]
+        multisig: MultisigArg,
+        /// Your account ID (base58, must be a member of the child multisig);
+        /// defaults to the account saved with the multisig
+        #[arg(long)]
+        account: Option<String>,
+        /// Parent multisig create_key (base58)
+        #[arg(long)]
+        parent: String,
+        /// Parent proposal index to vote on
+        #[arg(long)]
+        parent_index: u64,
+        /// Vote to reject instead of approve
+        #[arg(long)]
+        reject: bool,
+        /// Proposal title/description shown to members before they vote
+        #[arg(long, default_value = "")]
+        memo: String,
+        /// Hash of an off-chain document backing the proposal (hex, 32 bytes)
+        #[arg(long)]
+        content_hash: Option<String>,
+    },
+
+    /// Propose allowing any signer (e.g. a relayer) to execute approved proposals
+    SetPermissionlessExecution {
+        #[command(flatten)]
+        multisig: MultisigArg,
+        /// Your account ID (base58, must be a member);
+        /// defaults to the account saved with the multisig
+        #[arg(long)]
+        account: Option<String>,
+        /// Enable (true) or disable (false) permissionless execution
+        #[arg(long, action = clap::ArgAction::Set)]
+        enabled: bool,
+        /// Proposal title/description shown to members before they vote
+        #[arg(long, default_value = "")]
+        memo: String,
+        /// Hash of an off-chain document backing the proposal (hex, 32 bytes)
+        #[arg(long)]
+        content_hash: Option<String>,
+    },
+
+    /// Propose a reject threshold: this many rejections kill a proposal
+    SetRejectThreshold {
+        #[command(flatten)]
+        multisig: MultisigArg,
+        /// Your account ID (base58, must be a member);
+        /// defaults to the account saved with the multisig
+        #[arg(long)]
+        account: Option<String>,
+        /// Number of rejections that kill a proposal. Omit to clear the setting.
+        #[arg(long)]
+        cutoff: Option<u8>,
+        /// Proposal title/description shown to members before they vote
+        #[arg(long, default_value = "")]
+        memo: String,
+        /// Hash of an off-chain document backing the proposal (hex, 32 bytes)
+        #[arg(long)]
+        content_hash: Option<String>,
     },
 
-    /// Show multisig status
-    Status,
+    /// Upgrade a multisig's on-chain accounts to the current schema version
+    Migrate {
+        #[command(flatten)]
+        multisig: MultisigArg,
+        /// Your account ID (base58, must be a member);
+        /// defaults to the account saved with the multisig
+        #[arg(long)]
+        account: Option<String>,
+        /// Proposal indices whose accounts should be migrated too
+        #[arg(long, num_args = 0..)]
+        proposal: Vec<u64>,
+    },
+
+    /// Show a multisig's on-chain configuration and vault
+    #[command(alias = "status")]
+    Info {
+        #[command(flatten)]
+        multisig: MultisigArg,
+    },
+
+    /// Sign a transaction written by `--unsigned-out` (works offline)
+    Sign {
+        /// Unsigned transaction file
+        #[arg(long)]
+        tx: String,
+        /// Where to write the signed transaction
+        #[arg(long)]
+        out: String,
+    },
+
+    /// Submit a transaction signed with `sign`
+    Submit {
+        /// Signed transaction file
+        #[arg(long)]
+        tx: String,
+    },
+
+    /// Manage multisigs saved in the config file
+    #[command(subcommand)]
+    Config(ConfigCommand),
+
+    /// Review pending proposals of the saved multisigs and vote on them interactively
+    Tui {
+        /// Member account ID (base58) to vote with, instead of each multisig's saved account
+        #[arg(long)]
+        account: Option<String>,
+    },
 
     /// Generate shell completions
     Completions {
@@ -160,33 +569,221 @@ enum Commands {
     },
 }
 
-fn load_program(path: &str) -> (Program, nssa::ProgramId) {
-    let bytecode = std::fs::read(path)
-        .unwrap_or_else(|e| {
-            eprintln!("Error: Cannot read program binary at '{}': {}", path, e);
-            eprintln!("  Build it first:  cargo risczero build --manifest-path methods/guest/Cargo.toml");
-            eprintln!("  Or set path:     --program <path> or MULTISIG_PROGRAM=<path>");
-            std::process::exit(1);
-        });
+impl Commands {
+    /// The multisig this command acts on, if any.
+    fn multisig(&self) -> Option<&MultisigArg> {
+        match self {
+            Commands::Propose { multisig, .. }
+            | Commands::Transfer { multisig, .. }
+            | Commands::Activate { multisig, .. }
+            | Commands::Expire { multisig, .. }
+            | Commands::Proposal(ProposalCommand::List { multisig, .. })
+            | Commands::Proposal(ProposalCommand::Show { multisig, .. })
+            | Commands::Inspect { multisig, .. }
+            | Commands::Approve { multisig, .. }
+            | Commands::Reject { multisig, .. }
+            | Commands::Execute { multisig, .. }
+            | Commands::AddMember { multisig, .. }
+            | Commands::RemoveMember { multisig, .. }
+            | Commands::ChangeThreshold { multisig, .. }
+            | Commands::ProposeNestedVote { multisig, .. }
+            | Commands::SetPermissionlessExecution { multisig, .. }
+            | Commands::SetRejectThreshold { multisig, .. }
+            | Commands::Migrate { multisig, .. }
+            | Commands::Info { multisig } => Some(multisig),
+            _ => None,
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Output
+// ---------------------------------------------------------------------------
+//
+// In text mode commands print as they go. Otherwise that text is
+// suppressed; commands `record` their results on the `Ctx` instead, and in
+// JSON mode `main` prints them as a single object once the command finishes
+// or fails:
+//   {"ok": true, "proposal_index": 7, "proposal_pda": "...", "transactions": [...]}
+//   {"ok": false, "error": "...", ...whatever was recorded so far}
+
+/// Per-invocation state the command helpers share.
+struct Ctx {
+    format: OutputFormat,
+    /// Fields of the JSON report
+    report: serde_json::Map<String, Value>,
+    /// Path given with `--unsigned-out`: member-signed transactions are
+    /// written there instead of being signed and submitted
+    unsigned_out: Option<String>,
+    /// `--dry-run`: transactions are run locally instead of being submitted
+    dry_run: bool,
+}
+
+/// `println!` in text mode, nothing otherwise.
+macro_rules! say {
+    ($cx:expr, $($arg:tt)*) => {
+        if $cx.format == OutputFormat::Text {
+            println!($($arg)*);
+        }
+    };
+}
+
+/// What became of a transaction a command built.
+enum Outcome {
+    /// Submitted and confirmed
+    Confirmed { label: String, tx_hash: String },
+    /// Run locally only (`--dry-run`)
+    Simulated,
+    /// Written unsigned to this file (`--unsigned-out`)
+    Unsigned { path: String },
+}
+
+impl Ctx {
+    fn new(format: OutputFormat) -> Self {
+        Self { format, report: Default::default(), unsigned_out: None, dry_run: false }
+    }
+
+    /// Add a field to the JSON report.
+    fn record(&mut self, key: &str, value: impl Into<Value>) {
+        self.report.insert(key.to_string(), value.into());
+    }
+
+    /// Append to an array field of the JSON report.
+    fn record_push(&mut self, key: &str, value: impl Into<Value>) {
+        let entry = self.report.entry(key.to_string()).or_insert_with(|| Value::Array(vec![]));
+        if let Value::Array(items) = entry {
+            items.push(value.into());
+        }
+    }
+
+    fn record_proposal(&mut self, index: u64, proposal_pda: AccountId) {
+        self.record("proposal_index", index);
+        self.record("proposal_pda", proposal_pda.to_string());
+    }
+
+    fn record_outcome(&mut self, outcome: Outcome) {
+        match outcome {
+            Outcome::Confirmed { label, tx_hash } => {
+                self.record_push("transactions", json!({ "label": label, "tx_hash": tx_hash }));
+            }
+            Outcome::Simulated => self.record("dry_run", true),
+            Outcome::Unsigned { path } => self.record("unsigned_tx", path),
+        }
+    }
+
+    /// Report a non-fatal problem.
+    fn warn(&mut self, message: impl std::fmt::Display) {
+        if self.format == OutputFormat::Text {
+            eprintln!("⚠️  {}", message);
+        } else {
+            self.record_push("warnings", message.to_string());
+        }
+    }
+
+    /// Print the outcome of the command — the JSON report in JSON mode, the
+    /// error in text mode — and turn it into the exit code.
+    fn finish(mut self, result: Result<(), String>) -> ExitCode {
+        match self.format {
+            OutputFormat::Json => {
+                self.record("ok", result.is_ok());
+                if let Err(e) = &result {
+                    self.record("error", e.clone());
+                }
+                println!("{}", Value::Object(self.report));
+            }
+            _ => {
+                if let Err(e) = &result {
+                    eprintln!("❌ {}", e);
+                }
+            }
+        }
+        if result.is_ok() { ExitCode::SUCCESS } else { ExitCode::FAILURE }
+    }
+}
+
+fn program_id_hex(id: &nssa::ProgramId) -> String {
+    hex::encode(id.iter().flat_map(|word| word.to_le_bytes()).collect::<Vec<u8>>())
+}
+
+fn state_json(state: &multisig_core::MultisigState) -> Value {
+    json!({
+        "create_key": AccountId::new(state.create_key).to_string(),
+        "threshold": state.threshold,
+        "member_count": state.member_count,
+        "members": state.members.iter().map(|m| AccountId::new(*m).to_string()).collect::<Vec<_>>(),
+        "transaction_index": state.transaction_index,
+        "permissionless_execution": state.permissionless_execution,
+        "reject_threshold": state.reject_threshold,
+        "migrated_to": state.migrated_to.as_ref().map(program_id_hex),
+    })
+}
+
+fn proposal_json(
+    proposal: &multisig_core::Proposal,
+    state: &multisig_core::MultisigState,
+    program_id: &nssa::ProgramId,
+    token_program_id: Option<&nssa::ProgramId>,
+) -> Value {
+    let ids = |ids: &[[u8; 32]]| ids.iter().map(|id| AccountId::new(*id).to_string()).collect::<Vec<_>>();
+    json!({
+        "index": proposal.index,
+        "status": format!("{:?}", proposal.status),
+        "proposer": AccountId::new(proposal.proposer).to_string(),
+        "memo": proposal.memo,
+        "content_hash": proposal.content_hash.map(hex::encode),
+        "approvals": proposal.approval_count(state),
+        "threshold": state.threshold,
+        "rejections": proposal.rejection_count(state),
+        "approved": ids(&proposal.approved),
+        "rejected": ids(&proposal.rejected),
+        "action": describe_action(proposal, program_id),
+        "target_program_id": proposal.config_action.is_none().then(|| program_id_hex(&proposal.target_program_id)),
+        "instruction": proposal.config_action.is_none().then(|| describe_instruction(proposal, program_id, token_program_id)),
+        "target_account_ids": ids(&proposal.target_account_ids),
+        "authorized_indices": proposal.authorized_indices,
+        "depends_on": proposal.depends_on,
+        "recurrence": proposal.recurrence.as_ref().map(|r| json!({
+            "clock_account_id": AccountId::new(r.clock_account_id).to_string(),
+            "interval": r.interval,
+            "max_runs": r.max_runs,
+            "runs_executed": proposal.runs_executed,
+            "next_run_at": proposal.next_run_at(),
+        })),
+        "expiry": proposal.expiry.as_ref().map(|e| json!({
+            "clock_account_id": AccountId::new(e.clock_account_id).to_string(),
+            "expires_at": e.expires_at,
+        })),
+        "post_conditions": proposal.post_conditions.iter().map(|c| format!("{:?}", c)).collect::<Vec<_>>(),
+    })
+}
+
+fn load_program(path: &str) -> Result<(Program, nssa::ProgramId), String> {
+    let bytecode = std::fs::read(path).map_err(|e| format!(
+        "Cannot read program binary at '{}': {}\n  Build it first:  cargo risczero build --manifest-path methods/guest/Cargo.toml\n  Or set path:     --program <path> or MULTISIG_PROGRAM=<path>",
+        path, e
+    ))?;
     let program = Program::new(bytecode)
-        .unwrap_or_else(|e| {
-            eprintln!("Error: Invalid program bytecode at '{}': {:?}", path, e);
-            std::process::exit(1);
-        });
+        .map_err(|e| format!("Invalid program bytecode at '{}': {:?}", path, e))?;
     let id = program.id();
-    (program, id)
+    Ok((program, id))
 }
 
-async fn submit_and_confirm(wallet_core: &WalletCore, tx: PublicTransaction, label: &str) {
+async fn submit_and_confirm(
+    cx: &mut Ctx,
+    wallet_core: &WalletCore,
+    tx: PublicTransaction,
+    label: &str,
+) -> Result<Outcome, String> {
     let response = wallet_core
         .sequencer_client
         .send_tx_public(tx)
         .await
-        .unwrap();
+        .map_err(|e| format!("{} not submitted: {:?}", label, e))?;
 
-    println!("📤 {} submitted", label);
-    println!("   tx_hash: {}", response.tx_hash);
-    println!("   Waiting for confirmation...");
+    let tx_hash = response.tx_hash.to_string();
+    say!(cx, "📤 {} submitted", label);
+    say!(cx, "   tx_hash: {}", tx_hash);
+    say!(cx, "   Waiting for confirmation...");
 
     let poller = wallet::poller::TxPoller::new(
         wallet_core.config().clone(),
@@ -194,11 +791,11 @@ async fn submit_and_confirm(wallet_core: &WalletCore, tx: PublicTransaction, lab
     );
 
     match poller.poll_tx(response.tx_hash).await {
-        Ok(_) => println!("✅ Confirmed!"),
-        Err(e) => {
-            eprintln!("❌ Not confirmed: {e:#}");
-            std::process::exit(1);
+        Ok(_) => {
+            say!(cx, "✅ Confirmed!");
+            Ok(Outcome::Confirmed { label: label.to_string(), tx_hash })
         }
+        Err(e) => Err(format!("Not confirmed: {e:#}")),
     }
 }
 
@@ -206,136 +803,992 @@ async fn submit_and_confirm(wallet_core: &WalletCore, tx: PublicTransaction, lab
 /// `account_ids` is the full ordered account list for the instruction.
 /// `signer_id` is the one signing account (nonce provided only for it).
 async fn submit_signed_tx(
+    cx: &mut Ctx,
     wallet_core: &WalletCore,
     program_id: nssa::ProgramId,
     account_ids: Vec<AccountId>,
     signer_id: AccountId,
     instruction: Instruction,
     label: &str,
-) {
+) -> Result<Outcome, String> {
+    if cx.dry_run {
+        return dry_run(cx, wallet_core, &account_ids, Some(signer_id), &instruction, label).await;
+    }
+
     let nonces = wallet_core
         .get_accounts_nonces(vec![signer_id])
         .await
-        .expect("Failed to get nonces");
+        .map_err(|e| format!("Failed to get nonce of {}: {:?}", signer_id, e))?;
+
+    if let Some(path) = cx.unsigned_out.clone() {
+        let unsigned = UnsignedTx {
+            label: label.to_string(),
+            program_id: program_id_hex(&program_id),
+            account_ids: account_ids.iter().map(|id| id.to_string()).collect(),
+            signer: signer_id.to_string(),
+            nonces,
+            instruction,
+        };
+        write_json_file(&path, &unsigned)?;
+        say!(cx, "📝 {} written unsigned to {}", label, path);
+        say!(cx, "   Sign it offline: multisig sign --tx {} --out <signed file>", path);
+        return Ok(Outcome::Unsigned { path });
+    }
 
     let signing_key = wallet_core
         .storage()
         .user_data
         .get_pub_account_signing_key(signer_id)
-        .expect("Signing key not found — is this account in your wallet?");
+        .ok_or_else(|| format!("Signing key for {} not found — is this account in your wallet?", signer_id))?;
 
     let message = Message::try_new(
         program_id,
         account_ids,
         nonces,
         instruction,
-    ).unwrap();
+    ).map_err(|e| format!("Failed to build {} message: {:?}", label, e))?;
+
+    let witness_set = WitnessSet::for_message(&message, &[signing_key]);
+    let tx = PublicTransaction::new(message, witness_set);
+    submit_and_confirm(cx, wallet_core, tx, label).await
+}
+
+// ---------------------------------------------------------------------------
+// Dry run
+// ---------------------------------------------------------------------------
+//
+// Runs `multisig_program::process` natively on the accounts as they are on
+// chain now. No proof is generated and nothing is written. ChainedCalls are
+// reported but not run, so the target program's own checks (and any
+// post-conditions) are not simulated.
+
+async fn dry_run(
+    cx: &mut Ctx,
+    wallet_core: &WalletCore,
+    account_ids: &[AccountId],
+    signer_id: Option<AccountId>,
+    instruction: &Instruction,
+    label: &str,
+) -> Result<Outcome, String> {
+    let mut pre_states = Vec::with_capacity(account_ids.len());
+    for id in account_ids {
+        let account = wallet_core
+            .sequencer_client
+            .get_account(*id)
+            .await
+            .map_err(|e| format!("Failed to get account {}: {:?}", id, e))?
+            .account;
+        pre_states.push(AccountWithMetadata {
+            account,
+            is_authorized: Some(*id) == signer_id,
+            account_id: *id,
+        });
+    }
+
+    // The handlers report errors by panicking. The panic hook still prints
+    // the message to stderr; stdout (and so the JSON report) stays clean.
+    let result = std::panic::catch_unwind(AssertUnwindSafe(|| {
+        multisig_program::process(&pre_states, instruction)
+    }));
+
+    let (post_states, chained_calls) = result.map_err(|payload| {
+        let message = payload
+            .downcast_ref::<String>()
+            .cloned()
+            .or_else(|| payload.downcast_ref::<&str>().map(|s| s.to_string()))
+            .unwrap_or_else(|| "unknown error".to_string());
+        format!("{} would fail: {}", label, message)
+    })?;
+
+    say!(cx, "🧪 Dry run: {} would succeed", label);
+    for (pre, post) in pre_states.iter().zip(&post_states) {
+        let post = post.account();
+        let change = if *post == pre.account { "unchanged" } else { "changed" };
+        say!(cx, "   {} {}", pre.account_id, change);
+        let data: Vec<u8> = post.data.clone().into();
+        if *post != pre.account {
+            if let Ok(state) = multisig_core::MultisigState::from_bytes(&data) {
+                say!(cx, "     state: {}-of-{}, {} proposals", state.threshold, state.member_count, state.transaction_index);
+            } else if let Ok(proposal) = multisig_core::Proposal::from_bytes(&data) {
+                say!(cx, "     proposal #{}: {:?}, {} approvals, {} rejections",
+                    proposal.index, proposal.status, proposal.approved.len(), proposal.rejected.len());
+            }
+        }
+        cx.record_push("post_states", json!({
+            "account_id": pre.account_id.to_string(),
+            "changed": *post != pre.account,
+            "data": hex::encode(&data),
+        }));
+    }
+    for call in &chained_calls {
+        say!(cx, "   ↪ ChainedCall to {} with {} account(s)", program_id_hex(&call.program_id), call.pre_states.len());
+        cx.record_push("chained_calls", json!({
+            "program_id": program_id_hex(&call.program_id),
+            "account_ids": call.pre_states.iter().map(|a| a.account_id.to_string()).collect::<Vec<_>>(),
+            "instruction_data": call.instruction_data,
+        }));
+    }
+    Ok(Outcome::Simulated)
+}
+
+// ---------------------------------------------------------------------------
+// Offline signing
+// ---------------------------------------------------------------------------
+//
+// build (online, `--unsigned-out`) → sign (offline, holds the key) → submit (online).
+// The signer's nonce is fixed at build time, so the signer must not send any
+// other transaction before the signed one is submitted.
+
+/// A transaction built online, in readable form so the offline signer can
+/// review what they are signing.
+#[derive(Serialize, Deserialize)]
+struct UnsignedTx {
+    label: String,
+    /// Program ID (hex)
+    program_id: String,
+    /// Full ordered account list (base58)
+    account_ids: Vec<String>,
+    /// The one signing account (base58)
+    signer: String,
+    /// Signer nonce at build time
+    nonces: Vec<u128>,
+    instruction: Instruction,
+}
+
+/// A signed transaction, ready for `submit`.
+#[derive(Serialize, Deserialize)]
+struct SignedTx {
+    label: String,
+    /// Borsh-encoded `PublicTransaction` (hex)
+    transaction: String,
+}
+
+fn write_json_file<T: Serialize>(path: &str, value: &T) -> Result<(), String> {
+    let text = serde_json::to_string_pretty(value).map_err(|e| format!("Cannot encode {}: {}", path, e))?;
+    std::fs::write(path, text).map_err(|e| format!("Cannot write {}: {}", path, e))
+}
 
+fn read_json_file<T: for<'de> Deserialize<'de>>(path: &str) -> Result<T, String> {
+    let text = std::fs::read_to_string(path).map_err(|e| format!("Cannot read {}: {}", path, e))?;
+    serde_json::from_str(&text).map_err(|e| format!("Invalid transaction file {}: {}", path, e))
+}
+
+/// Sign an unsigned transaction file with the signer's key from the local wallet.
+fn sign_tx_file(cx: &mut Ctx, wallet_core: &WalletCore, tx_path: &str, out_path: &str) -> Result<(), String> {
+    let unsigned: UnsignedTx = read_json_file(tx_path)?;
+    let signer_id = parse_account_id(&unsigned.signer, "signer account")?;
+    let account_ids = unsigned.account_ids.iter()
+        .map(|s| parse_account_id(s, "account"))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    say!(cx, "✍️  Signing {}", unsigned.label);
+    say!(cx, "   Program ID:   {}", unsigned.program_id);
+    say!(cx, "   Signer:       {}", signer_id);
+    for (i, id) in account_ids.iter().enumerate() {
+        say!(cx, "   Account #{}:   {}", i, id);
+    }
+    say!(cx, "   Instruction:  {:?}", unsigned.instruction);
+
+    let signing_key = wallet_core
+        .storage()
+        .user_data
+        .get_pub_account_signing_key(signer_id)
+        .ok_or_else(|| format!("Signing key of {} not found — is this account in your wallet?", signer_id))?;
+
+    let message = Message::try_new(
+        parse_program_id(&unsigned.program_id)?,
+        account_ids,
+        unsigned.nonces,
+        unsigned.instruction,
+    ).map_err(|e| format!("Failed to build {} message: {:?}", unsigned.label, e))?;
     let witness_set = WitnessSet::for_message(&message, &[signing_key]);
     let tx = PublicTransaction::new(message, witness_set);
-    submit_and_confirm(wallet_core, tx, label).await;
+
+    let signed = SignedTx {
+        label: unsigned.label,
+        transaction: hex::encode(borsh::to_vec(&tx).map_err(|e| format!("Failed to encode transaction: {}", e))?),
+    };
+    write_json_file(out_path, &signed)?;
+    say!(cx, "✅ Signed transaction written to {}", out_path);
+    cx.record("signed_tx", out_path.to_string());
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// Config
+// ---------------------------------------------------------------------------
+//
+// Multisigs can be saved under a name in a TOML file and then be referred to
+// by that name wherever a command takes a multisig:
+//
+//   [multisigs.treasury]
+//   create_key = "<base58>"
+//   program_id = "<hex>"                     # optional, instead of --program
+//   token_program_id = "<hex>"               # optional, decodes token calls
+//   account = "<base58>"                     # optional default for --account
+//   sequencer_url = "http://127.0.0.1:3040"  # optional, instead of the wallet's
+//
+//   multisig approve treasury -i 7
+
+#[derive(Default, Serialize, Deserialize)]
+struct Config {
+    #[serde(default)]
+    multisigs: BTreeMap<String, SavedMultisig>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+struct SavedMultisig {
+    /// Create key (base58)
+    create_key: String,
+    /// Program ID (hex)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    program_id: Option<String>,
+    /// Token program ID (hex)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    token_program_id: Option<String>,
+    /// Default member account (base58)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    account: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    sequencer_url: Option<String>,
+}
+
+/// Open the local wallet, talking to `sequencer_url` instead of the wallet's
+/// own sequencer if given.
+fn connect_wallet(sequencer_url: Option<&str>) -> Result<WalletCore, String> {
+    let mut wallet_core = WalletCore::from_env().map_err(|e| format!("Cannot open the wallet: {:?}", e))?;
+    if let Some(url) = sequencer_url {
+        let parsed = url.parse().map_err(|e| format!("Invalid sequencer URL {}: {}", url, e))?;
+        let client = SequencerClient::new(parsed)
+            .map_err(|e| format!("Cannot connect to sequencer {}: {:?}", url, e))?;
+        wallet_core.sequencer_client = Arc::new(client);
+    }
+    Ok(wallet_core)
+}
+
+const DEFAULT_PROGRAM_PATH: &str = "target/riscv32im-risc0-zkvm-elf/docker/multisig.bin";
+
+fn config_path(explicit: Option<&str>) -> Result<PathBuf, String> {
+    if let Some(path) = explicit {
+        return Ok(PathBuf::from(path));
+    }
+    let base = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+        .ok_or("Cannot locate the config directory; set HOME or pass --config")?;
+    Ok(base.join("multisig").join("config.toml"))
+}
+
+impl Config {
+    /// Read the config file; a missing file is an empty config.
+    fn load(path: &Path) -> Result<Self, String> {
+        match std::fs::read_to_string(path) {
+            Ok(text) => toml::from_str(&text)
+                .map_err(|e| format!("Invalid config file {}: {}", path.display(), e)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(format!("Cannot read config file {}: {}", path.display(), e)),
+        }
+    }
+
+    fn save(&self, path: &Path) -> Result<(), String> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .map_err(|e| format!("Cannot create {}: {}", dir.display(), e))?;
+        }
+        let text = toml::to_string_pretty(self).map_err(|e| format!("Failed to encode config: {}", e))?;
+        std::fs::write(path, text)
+            .map_err(|e| format!("Cannot write config file {}: {}", path.display(), e))
+    }
+
+    /// The saved entry a multisig argument names, if it is a name rather than a create key.
+    fn saved(&self, multisig: &MultisigArg) -> Option<&SavedMultisig> {
+        self.multisigs.get(multisig.as_str())
+    }
+
+    fn create_key(&self, multisig: &MultisigArg) -> Result<[u8; 32], String> {
+        match self.saved(multisig) {
+            Some(saved) => parse_create_key(&saved.create_key),
+            None if multisig.as_str().parse::<AccountId>().is_err() => Err(format!(
+                "No multisig named '{}' in the config file (see `multisig config list`)",
+                multisig
+            )),
+            None => parse_create_key(multisig.as_str()),
+        }
+    }
+
+    /// `--account` if given, else the account saved with the multisig.
+    fn account(&self, multisig: &MultisigArg, account: Option<String>) -> Result<AccountId, String> {
+        let account = account
+            .or_else(|| self.saved(multisig).and_then(|saved| saved.account.clone()))
+            .ok_or_else(|| format!("No --account given and none saved for multisig '{}'", multisig))?;
+        parse_account_id(&account, "account")
+    }
+}
+
+/// A member's vote on, or execution of, a proposal.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Vote {
+    Approve,
+    ApproveAndExecute,
+    Reject,
+    Execute,
+}
+
+impl Vote {
+    fn label(self) -> &'static str {
+        match self {
+            Vote::Approve => "Approve",
+            Vote::ApproveAndExecute => "ApproveAndExecute",
+            Vote::Reject => "Reject",
+            Vote::Execute => "Execute",
+        }
+    }
+
+    /// `post_states` only matter to executing votes.
+    fn instruction(self, proposal_index: u64, post_states: Vec<ExpectedAccount>) -> Instruction {
+        match self {
+            Vote::Approve => Instruction::Approve { proposal_index },
+            Vote::ApproveAndExecute => Instruction::ApproveAndExecute { proposal_index, post_states },
+            Vote::Reject => Instruction::Reject { proposal_index },
+            Vote::Execute => Instruction::Execute { proposal_index, post_states },
+        }
+    }
+}
+
+/// Build and submit `vote` on proposal `index`, signed by `signer`.
+///
+/// Account layout: [state_pda, signer, proposal_pda], followed for executing
+/// votes by [dependency_pda?, clock?, ...targets]. Dependency and clock
+/// accounts always come from the proposal; target accounts too, unless
+/// `targets` is given.
+#[allow(clippy::too_many_arguments)]
+async fn submit_vote(
+    cx: &mut Ctx,
+    wallet_core: &WalletCore,
+    program_id: nssa::ProgramId,
+    create_key: &[u8; 32],
+    index: u64,
+    signer: AccountId,
+    vote: Vote,
+    targets: Option<Vec<AccountId>>,
+    post_state_args: &PostStateArgs,
+) -> Result<Outcome, String> {
+    let multisig_state_id = compute_multisig_state_pda(&program_id, create_key);
+    let proposal_pda = compute_proposal_pda(&program_id, create_key, index);
+    let mut account_ids = vec![multisig_state_id, signer, proposal_pda];
+    let mut post_states = vec![];
+
+    if matches!(vote, Vote::Execute | Vote::ApproveAndExecute) {
+        let proposal = try_fetch_proposal(wallet_core, proposal_pda).await?
+            .ok_or_else(|| format!("No proposal found at {}", proposal_pda))?;
+        let recorded_ids = proposal.execute_account_ids(&program_id);
+        let expected_targets = proposal.expected_target_account_ids();
+        let prefix_len = recorded_ids
+            .len()
+            .checked_sub(expected_targets.len())
+            .ok_or("Proposal records more target accounts than it executes with")?;
+        let (prefix_ids, recorded_targets) = recorded_ids.split_at(prefix_len);
+        let target_ids = targets.unwrap_or_else(|| {
+            recorded_targets.iter().map(|id| AccountId::new(*id)).collect()
+        });
+        if target_ids.iter().map(|id| *id.value()).ne(expected_targets.iter().copied()) {
+            return Err("Target accounts differ from the ones recorded in the proposal".to_string());
+        }
+        for (i, target_id) in target_ids.iter().enumerate() {
+            say!(cx, "   Target #{}:    {}", i, target_id);
+        }
+        post_states = expected_post_states(cx, wallet_core, &proposal, &target_ids, post_state_args).await?;
+        account_ids.extend(prefix_ids.iter().map(|id| AccountId::new(*id)));
+        account_ids.extend(target_ids);
+    }
+
+    let instruction = vote.instruction(index, post_states);
+    submit_signed_tx(cx, wallet_core, program_id, account_ids, signer, instruction, vote.label()).await
+}
+
+/// The target accounts `proposal`'s post-conditions refer to, as they will be
+/// after its ChainedCall: their current state with the `--post-balance` and
+/// `--post-token-balance` values applied.
+async fn expected_post_states(
+    cx: &mut Ctx,
+    wallet_core: &WalletCore,
+    proposal: &multisig_core::Proposal,
+    target_ids: &[AccountId],
+    args: &PostStateArgs,
+) -> Result<Vec<ExpectedAccount>, String> {
+    let indices = post_condition_account_indices(&proposal.post_conditions);
+    let balances = args.post_balance.iter().map(|arg| parse_index_amount(arg)).collect::<Result<Vec<_>, _>>()?;
+    let token_balances = args.post_token_balance.iter().map(|arg| parse_index_amount(arg)).collect::<Result<Vec<_>, _>>()?;
+    if let Some((index, _)) = balances.iter().chain(&token_balances).find(|(index, _)| !indices.contains(index)) {
+        return Err(format!("Target account {} has no post-condition, so its state after the call is not checked", index));
+    }
+
+    let mut post_states = Vec::with_capacity(indices.len());
+    for index in indices {
+        let target_id = target_ids
+            .get(index as usize)
+            .ok_or_else(|| format!("Post-condition refers to missing target account {}", index))?;
+        let account = wallet_core
+            .sequencer_client
+            .get_account(*target_id)
+            .await
+            .map_err(|e| format!("Failed to get account {}: {:?}", target_id, e))?
+            .account;
+        let mut expected = ExpectedAccount::from(&account);
+        if let Some((_, balance)) = balances.iter().find(|(i, _)| *i == index) {
+            expected.balance = *balance;
+        }
+        if let Some((_, balance)) = token_balances.iter().find(|(i, _)| *i == index) {
+            let offset = TOKEN_HOLDING_BALANCE_OFFSET as usize;
+            if expected.data.len() < offset + 16 {
+                expected.data.resize(offset + 16, 0);
+            }
+            expected.data[offset..offset + 16].copy_from_slice(&balance.to_le_bytes());
+        }
+        say!(cx, "   Expect after: target #{} with balance {}", index, expected.balance);
+        post_states.push(expected);
+    }
+    Ok(post_states)
+}
+
+/// How often `propose` re-reads the next index after losing a race.
+const MAX_PROPOSE_ATTEMPTS: u32 = 3;
+
+/// Submit a `Propose` instruction at the next free index and return the
+/// index the proposal landed at.
+///
+/// The proposal PDA depends on the index, so a proposal landing first makes
+/// ours fail; the next index is then re-read and the proposal resubmitted.
+async fn propose_with_retry(
+    cx: &mut Ctx,
+    wallet_core: &WalletCore,
+    program_id: nssa::ProgramId,
+    create_key: &[u8; 32],
+    proposer_id: AccountId,
+    instruction: Instruction,
+    label: &str,
+) -> Result<u64, String> {
+    let multisig_state_id = compute_multisig_state_pda(&program_id, create_key);
+    let mut proposal_index = next_proposal_index(wallet_core, multisig_state_id).await?;
+    for attempt in 1..=MAX_PROPOSE_ATTEMPTS {
+        let proposal_pda = compute_proposal_pda(&program_id, create_key, proposal_index);
+        say!(cx, "   Proposal #{}  PDA: {}", proposal_index, proposal_pda);
+        cx.record_proposal(proposal_index, proposal_pda);
+
+        let result = submit_signed_tx(
+            cx, wallet_core, program_id,
+            vec![multisig_state_id, proposer_id, proposal_pda],
+            proposer_id,
+            instruction.clone(),
+            label,
+        ).await;
+        let e = match result {
+            Ok(outcome) => {
+                cx.record_outcome(outcome);
+                break;
+            }
+            Err(e) => e,
+        };
+
+        // A confirmation timeout does not mean the proposal is missing
+        let found = try_fetch_proposal(wallet_core, proposal_pda).await
+            .map_err(|fetch_error| format!("{}\n  and could not check for the proposal: {}", e, fetch_error))?;
+        if let Some(proposal) = found
+            && is_proposal_of(&proposal, proposer_id, &instruction)
+        {
+            say!(cx, "✅ Proposal #{} exists on chain", proposal_index);
+            break;
+        }
+        let next_index = next_proposal_index(wallet_core, multisig_state_id).await?;
+        if next_index <= proposal_index || attempt == MAX_PROPOSE_ATTEMPTS {
+            return Err(e);
+        }
+        say!(cx, "⚠️  Proposal #{} was taken by another proposal, retrying as #{}", proposal_index, next_index);
+        proposal_index = next_index;
+    }
+    Ok(proposal_index)
+}
+
+/// Whether `proposal` is the one `instruction`, sent by `proposer_id`, creates:
+/// same proposer and same payload, whatever votes it has gathered since.
+fn is_proposal_of(proposal: &multisig_core::Proposal, proposer_id: AccountId, instruction: &Instruction) -> bool {
+    let (memo, content_hash, config_action) = match instruction.clone() {
+        Instruction::Propose {
+            target_program_id,
+            target_instruction_data,
+            target_account_ids,
+            pda_seeds,
+            authorized_indices,
+            memo,
+            content_hash,
+            draft: _,
+            depends_on,
+            recurrence,
+            post_conditions,
+            expiry,
+        } => {
+            let same_call = proposal.target_program_id == target_program_id
+                && proposal.target_instruction_data == target_instruction_data
+                && proposal.target_account_ids == target_account_ids
+                && proposal.pda_seeds == pda_seeds
+                && proposal.authorized_indices == authorized_indices
+                && proposal.depends_on == depends_on
+                && proposal.recurrence == recurrence
+                && proposal.expiry == expiry
+                && proposal.post_conditions == post_conditions;
+            if !same_call {
+                return false;
+            }
+            (memo, content_hash, None)
+        }
+        Instruction::ProposeAddMember { new_member, memo, content_hash } => {
+            (memo, content_hash, Some(ConfigAction::AddMember { new_member }))
+        }
+        Instruction::ProposeRemoveMember { member, memo, content_hash } => {
+            (memo, content_hash, Some(ConfigAction::RemoveMember { member }))
+        }
+        Instruction::ProposeChangeThreshold { new_threshold, memo, content_hash } => {
+            (memo, content_hash, Some(ConfigAction::ChangeThreshold { new_threshold }))
+        }
+        Instruction::ProposeSetPermissionlessExecution { enabled, memo, content_hash } => {
+            (memo, content_hash, Some(ConfigAction::SetPermissionlessExecution { enabled }))
+        }
+        Instruction::ProposeSetRejectThreshold { reject_threshold, memo, content_hash } => {
+            (memo, content_hash, Some(ConfigAction::SetRejectThreshold { reject_threshold }))
+        }
+        Instruction::ProposeMigrateToProgram { new_program_id, handover_calls, memo, content_hash } => {
+            (memo, content_hash, Some(ConfigAction::MigrateToProgram { new_program_id, handover_calls }))
+        }
+        _ => return false,
+    };
+    proposal.proposer == *proposer_id.value()
+        && proposal.config_action == config_action
+        && proposal.memo == memo
+        && proposal.content_hash == content_hash
+}
+
+/// Index the next proposal of this multisig will get.
+async fn next_proposal_index(wallet_core: &WalletCore, state_pda: AccountId) -> Result<u64, String> {
+    let state = try_fetch_state(wallet_core, state_pda).await?
+        .ok_or_else(|| format!("No multisig found at {}", state_pda))?;
+    Ok(state.transaction_index + 1)
+}
+
+/// Fetch and decode a proposal account, or `None` if it is missing or
+/// undecodable. Fails only if the sequencer cannot be asked.
+async fn try_fetch_proposal(wallet_core: &WalletCore, proposal_pda: AccountId) -> Result<Option<multisig_core::Proposal>, String> {
+    let account = wallet_core
+        .sequencer_client
+        .get_account(proposal_pda)
+        .await
+        .map_err(|e| format!("Failed to get proposal {}: {:?}", proposal_pda, e))?;
+    let data: Vec<u8> = account.account.data.into();
+    Ok(multisig_core::Proposal::from_bytes(&data).ok())
+}
+
+/// Print proposal `index` in full: votes, action and decoded instruction,
+/// preceded with `raw` by the account bytes exactly as stored.
+async fn show_proposal(
+    cx: &mut Ctx,
+    wallet_core: &WalletCore,
+    program_id: nssa::ProgramId,
+    token_program_id: Option<&nssa::ProgramId>,
+    create_key: &[u8; 32],
+    index: u64,
+    raw: bool,
+) -> Result<(), String> {
+    let multisig_state_id = compute_multisig_state_pda(&program_id, create_key);
+    let proposal_pda = compute_proposal_pda(&program_id, create_key, index);
+    let state = try_fetch_state(wallet_core, multisig_state_id).await?
+        .ok_or_else(|| format!("No multisig found at {}", multisig_state_id))?;
+    let account = wallet_core
+        .sequencer_client
+        .get_account(proposal_pda)
+        .await
+        .map_err(|e| format!("Failed to get proposal {}: {:?}", proposal_pda, e))?;
+    let data: Vec<u8> = account.account.data.into();
+
+    say!(cx, "🔎 Proposal #{}", index);
+    say!(cx, "   PDA:          {}", proposal_pda);
+    cx.record("proposal_pda", proposal_pda.to_string());
+    if raw {
+        say!(cx, "   Bytes:        {}", data.len());
+        say!(cx, "   Data:         {}", hex::encode(&data));
+        cx.record("data", hex::encode(&data));
+    }
+    let proposal = multisig_core::Proposal::from_bytes(&data)
+        .map_err(|e| format!("Failed to decode proposal: {}", e))?;
+
+    cx.record("proposal", proposal_json(&proposal, &state, &program_id, token_program_id));
+    say!(cx, "   Status:       {:?}", proposal.status);
+    say!(cx, "   Proposer:     {}", AccountId::new(proposal.proposer));
+    print_memo(cx, &proposal.memo, proposal.content_hash.as_ref());
+    say!(cx, "   Approvals:    {}/{}", proposal.approval_count(&state), state.threshold);
+    for member in &proposal.approved {
+        let note = if state.is_member(member) { "" } else { " (no longer a member)" };
+        say!(cx, "     ✅ {}{}", AccountId::new(*member), note);
+    }
+    say!(cx, "   Rejections:   {}", proposal.rejection_count(&state));
+    for member in &proposal.rejected {
+        let note = if state.is_member(member) { "" } else { " (no longer a member)" };
+        say!(cx, "     ❌ {}{}", AccountId::new(*member), note);
+    }
+    say!(cx, "   Action:       {}", describe_action(&proposal, &program_id));
+    if proposal.config_action.is_none() {
+        say!(cx, "   Instruction:  {}", describe_instruction(&proposal, &program_id, token_program_id));
+        for (i, target_id) in proposal.target_account_ids.iter().enumerate() {
+            let auth = if proposal.authorized_indices.contains(&(i as u8)) { " (authorized)" } else { "" };
+            say!(cx, "   Target #{}:    {}{}", i, AccountId::new(*target_id), auth);
+        }
+    }
+    if let Some(dependency) = proposal.depends_on {
+        say!(cx, "   Depends on:   proposal #{}", dependency);
+    }
+    if let Some(recurrence) = &proposal.recurrence {
+        say!(cx, "   Recurring:    {}/{} runs, every {}s", proposal.runs_executed, recurrence.max_runs, recurrence.interval);
+        if let Some(next_at) = proposal.next_run_at() {
+            say!(cx, "   Next run at:  {}", next_at);
+        }
+    }
+    if let Some(expiry) = &proposal.expiry {
+        say!(cx, "   Expires at:   {}", expiry.expires_at);
+    }
+    for condition in &proposal.post_conditions {
+        say!(cx, "   Check after:  {:?}", condition);
+    }
+    Ok(())
+}
+
+/// One-line description of what a proposal does when executed.
+fn describe_action(proposal: &multisig_core::Proposal, program_id: &nssa::ProgramId) -> String {
+    match &proposal.config_action {
+        Some(ConfigAction::AddMember { new_member }) => format!("add member {}", AccountId::new(*new_member)),
+        Some(ConfigAction::RemoveMember { member }) => format!("remove member {}", AccountId::new(*member)),
+        Some(ConfigAction::ChangeThreshold { new_threshold }) => format!("change threshold to {}", new_threshold),
+        Some(ConfigAction::SetPermissionlessExecution { enabled }) => {
+            format!("{} permissionless execution", if *enabled { "enable" } else { "disable" })
+        }
+        Some(ConfigAction::SetRejectThreshold { reject_threshold: Some(cutoff) }) => {
+            format!("set reject threshold to {}", cutoff)
+        }
+        Some(ConfigAction::SetRejectThreshold { reject_threshold: None }) => "clear reject threshold".to_string(),
+        Some(ConfigAction::MigrateToProgram { new_program_id, handover_calls }) => {
+            format!("migrate to program {} with {} handover call(s)", program_id_hex(new_program_id), handover_calls.len())
+        }
+        None if proposal.target_program_id == *program_id => "call this multisig program".to_string(),
+        None => format!(
+            "call program {} with {} account(s)",
+            program_id_hex(&proposal.target_program_id),
+            proposal.target_account_ids.len()
+        ),
+    }
+}
+
+/// Decode a transfer proposal's instruction data where the target program is
+/// known (a multisig, e.g. a nested vote, or the token program); otherwise
+/// show the raw u32 words.
+fn describe_instruction(
+    proposal: &multisig_core::Proposal,
+    program_id: &nssa::ProgramId,
+    token_program_id: Option<&nssa::ProgramId>,
+) -> String {
+    let data = &proposal.target_instruction_data;
+    if proposal.target_program_id == *program_id
+        && let Ok(instruction) = risc0_zkvm::serde::from_slice::<Instruction, u32>(data)
+    {
+        return format!("{:?}", instruction);
+    }
+    if token_program_id == Some(&proposal.target_program_id)
+        && let Ok(instruction) = risc0_zkvm::serde::from_slice::<TokenInstruction, u32>(data)
+    {
+        return format!("token {:?}", instruction);
+    }
+    data.iter()
+        .map(|word| hex::encode(word.to_le_bytes()))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// How many nonces `create --label` tries before giving up.
+const MAX_CREATE_NONCE: u64 = 64;
+
+/// Fetch and decode a multisig state account, or `None` if no multisig lives
+/// there. Fails only if the sequencer cannot be asked.
+async fn try_fetch_state(wallet_core: &WalletCore, state_pda: AccountId) -> Result<Option<multisig_core::MultisigState>, String> {
+    let account = wallet_core
+        .sequencer_client
+        .get_account(state_pda)
+        .await
+        .map_err(|e| format!("Failed to get multisig state {}: {:?}", state_pda, e))?;
+    let data: Vec<u8> = account.account.data.into();
+    Ok(multisig_core::MultisigState::from_legacy_bytes(&data).ok())
 }
 
 /// Parse a hex string into a 32-byte array.
-fn parse_hex32(s: &str) -> [u8; 32] {
-    let bytes = hex::decode(s).expect("Invalid hex value (expected 64 hex chars for 32 bytes)");
-    if bytes.len() != 32 {
-        eprintln!("Error: expected 32 bytes (64 hex chars), got {}", bytes.len());
-        std::process::exit(1);
+fn parse_hex32(s: &str) -> Result<[u8; 32], String> {
+    let bytes = hex::decode(s).map_err(|_| format!("Invalid hex value (expected 64 hex chars for 32 bytes): {}", s))?;
+    bytes.try_into().map_err(|bytes: Vec<u8>| format!("expected 32 bytes (64 hex chars), got {}", bytes.len()))
+}
+
+/// Validate a proposal memo and parse the optional content hash.
+fn parse_memo(memo: &str, content_hash: Option<&str>) -> Result<Option<[u8; 32]>, String> {
+    if memo.len() > MAX_MEMO_LEN {
+        return Err(format!("memo is {} bytes, maximum is {}", memo.len(), MAX_MEMO_LEN));
     }
-    let mut arr = [0u8; 32];
-    arr.copy_from_slice(&bytes);
-    arr
+    content_hash.map(parse_hex32).transpose()
+}
+
+/// Print the memo/content hash lines shared by all propose commands.
+fn print_memo(cx: &Ctx, memo: &str, content_hash: Option<&[u8; 32]>) {
+    if !memo.is_empty() {
+        say!(cx, "   Memo:         {}", memo);
+    }
+    if let Some(hash) = content_hash {
+        say!(cx, "   Content hash: {}", hex::encode(hash));
+    }
+}
+
+/// Byte offset of the balance in a fungible token holding: variant tag,
+/// definition ID, then u128 (checked against `token_core::TokenHolding` in the tests).
+const TOKEN_HOLDING_BALANCE_OFFSET: u32 = 33;
+
+/// Parse a post-condition argument of the form "INDEX:AMOUNT".
+fn parse_index_amount(s: &str) -> Result<(u8, u128), String> {
+    let (index, amount) = s.split_once(':').ok_or_else(|| format!("Expected INDEX:AMOUNT, got {}", s))?;
+    Ok((
+        index.parse().map_err(|_| format!("Invalid target account index: {}", index))?,
+        amount.parse().map_err(|_| format!("Invalid amount: {}", amount))?,
+    ))
+}
+
+/// Parse a base58 account ID; `what` names it in the error.
+fn parse_account_id(s: &str, what: &str) -> Result<AccountId, String> {
+    s.parse().map_err(|_| format!("Invalid {} ID: {}", what, s))
 }
 
 /// Parse create_key from base58 AccountId string to [u8; 32].
-fn parse_create_key(s: &str) -> [u8; 32] {
-    let id: AccountId = s.parse().expect("Invalid multisig create_key (base58)");
-    *id.value()
+fn parse_create_key(s: &str) -> Result<[u8; 32], String> {
+    let id: AccountId = s.parse().map_err(|_| format!("Invalid multisig create_key (base58): {}", s))?;
+    Ok(*id.value())
 }
 
 /// Parse a ProgramId ([u32; 8]) from a 64-char hex string (32 bytes, interpreted as 8 little-endian u32s).
-fn parse_program_id(s: &str) -> nssa::ProgramId {
-    let bytes = hex::decode(s).unwrap_or_else(|_| {
-        eprintln!("Error: invalid hex for program ID (expected 64 hex chars): {}", s);
-        std::process::exit(1);
-    });
+fn parse_program_id(s: &str) -> Result<nssa::ProgramId, String> {
+    let bytes = hex::decode(s)
+        .map_err(|_| format!("invalid hex for program ID (expected 64 hex chars): {}", s))?;
     if bytes.len() != 32 {
-        eprintln!("Error: program ID must be 32 bytes (64 hex chars), got {}", bytes.len());
-        std::process::exit(1);
+        return Err(format!("program ID must be 32 bytes (64 hex chars), got {}", bytes.len()));
     }
     let mut id = [0u32; 8];
     for i in 0..8 {
         id[i] = u32::from_le_bytes([bytes[i*4], bytes[i*4+1], bytes[i*4+2], bytes[i*4+3]]);
     }
-    id
+    Ok(id)
 }
 
 /// Parse hex-encoded u32 words into Vec<u32>.
 /// Each word is a hex string like "01000000" (little-endian u32) or a plain u32 decimal.
-fn parse_instruction_data(args: &[String]) -> Vec<u32> {
+fn parse_instruction_data(args: &[String]) -> Result<Vec<u32>, String> {
     args.iter().map(|s| {
         // Try hex first
         if let Ok(bytes) = hex::decode(s) {
             if bytes.len() == 4 {
-                return u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+                return Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]));
             }
         }
         // Fall back to decimal
-        s.parse::<u32>().unwrap_or_else(|_| {
-            eprintln!("Error: instruction data word '{}' is neither valid 4-byte hex nor decimal u32", s);
-            std::process::exit(1);
+        s.parse::<u32>().map_err(|_| {
+            format!("instruction data word '{}' is neither valid 4-byte hex nor decimal u32", s)
         })
     }).collect()
 }
 
 #[tokio::main]
-async fn main() {
+async fn main() -> ExitCode {
     let cli = Cli::parse();
 
-    // Commands that don't need wallet/program
-    match &cli.command {
-        Commands::Completions { shell } => {
-            generate(*shell, &mut Cli::command(), "multisig", &mut std::io::stdout());
-            return;
-        }
-        Commands::Status => {
-            println!("📊 Multisig Status");
-            println!("   Program path:   {}", cli.program);
-            if let Ok(bytecode) = std::fs::read(&cli.program) {
-                if let Ok(program) = Program::new(bytecode) {
-                    println!("   Program ID:     {:?}", program.id());
+    if let Commands::Completions { shell } = &cli.command {
+        generate(*shell, &mut Cli::command(), "multisig", &mut std::io::stdout());
+        return ExitCode::SUCCESS;
+    }
+
+    let mut cx = Ctx::new(cli.output);
+    let result = run(&mut cx, cli).await;
+    cx.finish(result)
+}
+
+async fn run(cx: &mut Ctx, cli: Cli) -> Result<(), String> {
+    let config_file = config_path(cli.config.as_deref())?;
+    let mut config = Config::load(&config_file)?;
+
+    // Commands that only touch the config file
+    if let Commands::Config(command) = &cli.command {
+        match command {
+            ConfigCommand::Add { name, create_key, program_id, token_program_id, account, sequencer_url } => {
+                parse_create_key(create_key)?;
+                if let Some(id) = program_id {
+                    parse_program_id(id)?;
                 }
-            } else {
-                println!("   Program binary: not found");
+                if let Some(id) = token_program_id {
+                    parse_program_id(id)?;
+                }
+                if let Some(id) = account {
+                    parse_account_id(id, "account")?;
+                }
+                let saved = SavedMultisig {
+                    create_key: create_key.clone(),
+                    program_id: program_id.clone(),
+                    token_program_id: token_program_id.clone(),
+                    account: account.clone(),
+                    sequencer_url: sequencer_url.clone(),
+                };
+                if config.multisigs.insert(name.clone(), saved).is_some() {
+                    say!(cx, "Replacing saved multisig '{}'", name);
+                }
+                config.save(&config_file)?;
+                say!(cx, "✅ Saved multisig '{}' to {}", name, config_file.display());
             }
-            println!("   Use --create-key with 'create' or --multisig with other commands");
-            return;
+            ConfigCommand::Remove { name } => {
+                if config.multisigs.remove(name).is_none() {
+                    return Err(format!("No multisig named '{}' in {}", name, config_file.display()));
+                }
+                config.save(&config_file)?;
+                say!(cx, "✅ Removed multisig '{}'", name);
+            }
+            ConfigCommand::List => {
+                if config.multisigs.is_empty() {
+                    say!(cx, "No saved multisigs in {}", config_file.display());
+                }
+                for (name, saved) in &config.multisigs {
+                    say!(cx, "{}", name);
+                    say!(cx, "   Create key:   {}", saved.create_key);
+                    if let Some(id) = &saved.program_id {
+                        say!(cx, "   Program ID:   {}", id);
+                    }
+                    if let Some(id) = &saved.token_program_id {
+                        say!(cx, "   Token program: {}", id);
+                    }
+                    if let Some(account) = &saved.account {
+                        say!(cx, "   Account:      {}", account);
+                    }
+                    if let Some(url) = &saved.sequencer_url {
+                        say!(cx, "   Sequencer:    {}", url);
+                    }
+                }
+                cx.record("multisigs", serde_json::to_value(&config.multisigs).unwrap());
+            }
+        }
+        cx.record("config", config_file.display().to_string());
+        return Ok(());
+    }
+
+    cx.unsigned_out = cli.unsigned_out.clone();
+    cx.dry_run = cli.dry_run;
+
+    if let Commands::Tui { account } = &cli.command {
+        let account = account.as_deref().map(|id| parse_account_id(id, "account")).transpose()?;
+        let mut fallback_program_id = None;
+        let mut multisigs = Vec::with_capacity(config.multisigs.len());
+        for (name, saved) in &config.multisigs {
+            let program_id = match (&saved.program_id, fallback_program_id) {
+                (Some(id), _) if cli.program.is_none() => parse_program_id(id)?,
+                (_, Some(id)) => id,
+                (_, None) => {
+                    let (_, id) = load_program(cli.program.as_deref().unwrap_or(DEFAULT_PROGRAM_PATH))?;
+                    *fallback_program_id.insert(id)
+                }
+            };
+            multisigs.push(tui::TuiMultisig {
+                name: name.clone(),
+                create_key: parse_create_key(&saved.create_key)?,
+                program_id,
+                token_program_id: saved.token_program_id.as_deref().map(parse_program_id).transpose()?,
+                account: match (account, &saved.account) {
+                    (Some(account), _) => Some(account),
+                    (None, Some(id)) => Some(parse_account_id(id, "account")?),
+                    (None, None) => None,
+                },
+                wallet: connect_wallet(saved.sequencer_url.as_deref())?,
+            });
+        }
+        if multisigs.is_empty() {
+            return Err(format!("No saved multisigs in {} — add one with `multisig config add`", config_file.display()));
+        }
+        return tui::run(multisigs).await;
+    }
+
+    let saved = cli.command.multisig().and_then(|multisig| config.saved(multisig)).cloned();
+    let wallet_core = connect_wallet(saved.as_ref().and_then(|saved| saved.sequencer_url.as_deref()))?;
+
+    // Commands that don't need the program binary (e.g. on an offline signing machine)
+    match &cli.command {
+        Commands::Sign { tx, out } => return sign_tx_file(cx, &wallet_core, tx, out),
+        Commands::Submit { tx } => {
+            let signed: SignedTx = read_json_file(tx)?;
+            let bytes = hex::decode(&signed.transaction)
+                .map_err(|e| format!("Invalid transaction hex in {}: {}", tx, e))?;
+            let transaction: PublicTransaction = borsh::from_slice(&bytes)
+                .map_err(|e| format!("Invalid transaction in {}: {}", tx, e))?;
+            let outcome = submit_and_confirm(cx, &wallet_core, transaction, &signed.label).await?;
+            cx.record_outcome(outcome);
+            return Ok(());
         }
         _ => {}
     }
 
-    let wallet_core = WalletCore::from_env().unwrap();
-    let (_, program_id) = load_program(&cli.program);
+    // An explicit --program wins over the program ID saved with the multisig
+    let program_id = match (&cli.program, saved.as_ref().and_then(|saved| saved.program_id.as_ref())) {
+        (None, Some(id)) => parse_program_id(id)?,
+        (path, _) => load_program(path.as_deref().unwrap_or(DEFAULT_PROGRAM_PATH))?.1,
+    };
+    let saved_token_program_id = saved.as_ref()
+        .and_then(|saved| saved.token_program_id.as_deref())
+        .map(parse_program_id)
+        .transpose()?;
 
     match cli.command {
         // ── Create ──────────────────────────────────────────────────────
         //
         // Account layout: [state_pda, member1, member2, ..., memberN]
         // No signer required — anyone can create.
-        Commands::Create { threshold, member, create_key } => {
-            let members: Vec<AccountId> = member.iter()
-                .map(|s| s.parse().expect("Invalid member ID"))
-                .collect();
+        Commands::Create { threshold, member, create_key, label, creator, nonce, save_as } => {
+            // Create carries no signature, so there is nothing to sign offline.
+            if cx.unsigned_out.is_some() {
+                return Err("create needs no signature; --unsigned-out is not supported, submit it directly".to_string());
+            }
+            let members = member.iter()
+                .map(|s| parse_account_id(s, "member"))
+                .collect::<Result<Vec<_>, _>>()?;
 
             if (threshold as usize) > members.len() {
-                eprintln!("Error: threshold ({}) > members ({})", threshold, members.len());
-                std::process::exit(1);
+                return Err(format!("threshold ({}) > members ({})", threshold, members.len()));
             }
 
-            // Generate or use provided create_key
+            // Generate, derive or use provided create_key
             let ck: [u8; 32] = if let Some(ref key_str) = create_key {
-                parse_create_key(key_str)
+                parse_create_key(key_str)?
+            } else if let (Some(label), Some(creator)) = (&label, &creator) {
+                let creator_id = parse_account_id(creator, "creator account")?;
+                let nonce = match nonce {
+                    Some(n) => n,
+                    None => {
+                        let mut free = None;
+                        for n in 0..MAX_CREATE_NONCE {
+                            let ck = derive_create_key(creator_id.value(), label, n);
+                            let pda = compute_multisig_state_pda(&program_id, &ck);
+                            if try_fetch_state(&wallet_core, pda).await?.is_none() {
+                                free = Some(n);
+                                break;
+                            }
+                        }
+                        free.ok_or_else(|| format!(
+                            "Nonces 0..{} of this label are all taken; pick another label or pass --nonce",
+                            MAX_CREATE_NONCE,
+                        ))?
+                    }
+                };
+                say!(cx, "🔑 Deriving create key from creator {}, label {:?}, nonce {}", creator_id, label, nonce);
+                derive_create_key(creator_id.value(), label, nonce)
             } else {
                 let random_key = nssa::PrivateKey::new_os_random();
                 let pk = nssa::PublicKey::new_from_private_key(&random_key);
@@ -344,9 +1797,10 @@ async fn main() {
 
             let multisig_state_id = compute_multisig_state_pda(&program_id, &ck);
 
-            println!("🔐 Creating {}-of-{} multisig", threshold, members.len());
-            println!("   Create key: {}", AccountId::new(ck));
-            println!("   State PDA:  {}", multisig_state_id);
+            say!(cx, "🔐 Creating {}-of-{} multisig", threshold, members.len());
+            say!(cx, "   Create key: {}", AccountId::new(ck));
+            say!(cx, "   State PDA:  {}", multisig_state_id);
+            cx.record("state_pda", multisig_state_id.to_string());
 
             let instruction = Instruction::CreateMultisig {
                 create_key: ck,
@@ -358,18 +1812,73 @@ async fn main() {
             let mut account_ids = vec![multisig_state_id];
             account_ids.extend(members.iter().copied());
 
-            let message = Message::try_new(
-                program_id,
-                account_ids,
-                vec![],
-                instruction,
-            ).unwrap();
-            let witness_set = WitnessSet::for_message(&message, &[] as &[&nssa::PrivateKey]);
-            let tx = PublicTransaction::new(message, witness_set);
-            submit_and_confirm(&wallet_core, tx, "Create multisig").await;
+            let outcome = if cx.dry_run {
+                dry_run(cx, &wallet_core, &account_ids, None, &instruction, "Create multisig").await?
+            } else {
+                let message = Message::try_new(
+                    program_id,
+                    account_ids,
+                    vec![],
+                    instruction,
+                ).map_err(|e| format!("Failed to build Create multisig message: {:?}", e))?;
+                let witness_set = WitnessSet::for_message(&message, &[] as &[&nssa::PrivateKey]);
+                let tx = PublicTransaction::new(message, witness_set);
+                submit_and_confirm(cx, &wallet_core, tx, "Create multisig").await?
+            };
+            cx.record_outcome(outcome);
+
+            cx.record("create_key", AccountId::new(ck).to_string());
+            cx.record("vault_pda", compute_vault_pda(&program_id, &ck).to_string());
+            say!(cx, "\n💡 Save this create key to interact with the multisig:");
+            say!(cx, "   {}", AccountId::new(ck));
+            if let (Some(label), Some(creator)) = (&label, &creator) {
+                say!(cx, "   (or recover it with: multisig recover --creator {} --label {:?})", creator, label);
+            }
+            if let Some(name) = save_as.filter(|_| !cx.dry_run) {
+                config.multisigs.insert(name.clone(), SavedMultisig {
+                    create_key: AccountId::new(ck).to_string(),
+                    program_id: Some(program_id_hex(&program_id)),
+                    token_program_id: None,
+                    account: None,
+                    sequencer_url: None,
+                });
+                config.save(&config_file)?;
+                say!(cx, "   Saved as '{}' in {}", name, config_file.display());
+            }
+        }
+
+        // ── Recover ─────────────────────────────────────────────────────
+        //
+        // Read-only: re-derives create keys for nonces 0..scan and reports
+        // every one whose state PDA holds a multisig.
+        Commands::Recover { creator, label, scan } => {
+            let creator_id = parse_account_id(&creator, "creator account")?;
+
+            say!(cx, "🔍 Scanning nonces 0..{} for creator {}, label {:?}", scan, creator_id, label);
+
+            let mut found = 0;
+            for nonce in 0..scan {
+                let ck = derive_create_key(creator_id.value(), &label, nonce);
+                let multisig_state_id = compute_multisig_state_pda(&program_id, &ck);
+                if let Some(state) = try_fetch_state(&wallet_core, multisig_state_id).await? {
+                    found += 1;
+                    say!(cx, "\n   Nonce {}:", nonce);
+                    say!(cx, "   Create key: {}", AccountId::new(ck));
+                    say!(cx, "   State PDA:  {}", multisig_state_id);
+                    say!(cx, "   Threshold:  {}-of-{}", state.threshold, state.member_count);
+                    cx.record_push("multisigs", json!({
+                        "nonce": nonce,
+                        "create_key": AccountId::new(ck).to_string(),
+                        "state_pda": multisig_state_id.to_string(),
+                        "state": state_json(&state),
+                    }));
+                }
+            }
 
-            println!("\n💡 Save this create key to interact with the multisig:");
-            println!("   {}", AccountId::new(ck));
+            if found == 0 {
+                cx.record("multisigs", Value::Array(vec![]));
+                say!(cx, "   No multisigs found. Check the creator, label and program binary, or raise --scan.");
+            }
         }
 
         // ── Propose ─────────────────────────────────────────────────────
@@ -381,68 +1890,340 @@ async fn main() {
             account,
             target_program,
             instruction_data,
-            target_account_count,
+            target_account,
             pda_seed,
             authorized_index,
-            proposal_index,
+            memo,
+            content_hash,
+            draft,
+            depends_on,
+            every,
+            runs,
+            expires_at,
+            clock,
+            max_decrease,
+            max_token_decrease,
+            must_exist,
         } => {
-            let ck = parse_create_key(&multisig);
+            let ck = config.create_key(&multisig)?;
             let multisig_state_id = compute_multisig_state_pda(&program_id, &ck);
-            let account_id: AccountId = account.parse().expect("Invalid account ID");
-            let proposal_pda = compute_proposal_pda(&program_id, &ck, proposal_index);
+            let account_id = config.account(&multisig, account)?;
+
+            let target_program_id: nssa::ProgramId = parse_program_id(&target_program)?;
 
-            let target_program_id: nssa::ProgramId = parse_program_id(&target_program);
+            let target_instruction_data = parse_instruction_data(&instruction_data)?;
 
-            let target_instruction_data = parse_instruction_data(&instruction_data);
+            let target_account_ids = target_account.iter()
+                .map(|s| parse_account_id(s, "target account"))
+                .collect::<Result<Vec<_>, _>>()?;
 
-            let pda_seeds: Vec<[u8; 32]> = pda_seed.iter()
+            let pda_seeds = pda_seed.iter()
                 .map(|s| parse_hex32(s))
-                .collect();
+                .collect::<Result<Vec<_>, _>>()?;
 
-            println!("📝 Creating proposal #{}...", proposal_index);
-            println!("   State PDA:    {}", multisig_state_id);
-            println!("   Proposer:     {}", account_id);
-            println!("   Proposal PDA: {}", proposal_pda);
+            let content_hash = parse_memo(&memo, content_hash.as_deref())?;
+
+            say!(cx, "📝 Creating {}proposal...", if draft { "draft " } else { "" });
+            say!(cx, "   State PDA:    {}", multisig_state_id);
+            cx.record("state_pda", multisig_state_id.to_string());
+            say!(cx, "   Proposer:     {}", account_id);
+            for (i, target_id) in target_account_ids.iter().enumerate() {
+                say!(cx, "   Target #{}:    {}", i, target_id);
+            }
+            if let Some(dependency) = depends_on {
+                say!(cx, "   Depends on:   proposal #{}", dependency);
+            }
+            if clock.is_some() && every.is_none() && expires_at.is_none() {
+                return Err("--clock is only used with --every or --expires-at".to_string());
+            }
+            let clock_account_id = clock.as_deref()
+                .map(|s| parse_account_id(s, "clock account"))
+                .transpose()?
+                .map(|id| *id.value());
+            let recurrence = every.map(|interval| multisig_core::Recurrence {
+                clock_account_id: clock_account_id.unwrap(),
+                interval,
+                max_runs: runs.unwrap(),
+            });
+            if let Some(recurrence) = &recurrence {
+                say!(cx, "   Recurring:    {} runs, every {}s (clock {})",
+                    recurrence.max_runs, recurrence.interval, AccountId::new(recurrence.clock_account_id));
+            }
+            let expiry = expires_at.map(|expires_at| multisig_core::Expiry {
+                clock_account_id: clock_account_id.unwrap(),
+                expires_at,
+            });
+            if let Some(expiry) = &expiry {
+                say!(cx, "   Expires at:   {} (clock {})", expiry.expires_at, AccountId::new(expiry.clock_account_id));
+            }
+            let mut post_conditions = Vec::new();
+            for arg in &max_decrease {
+                let (account_index, max_decrease) = parse_index_amount(arg)?;
+                post_conditions.push(PostCondition::MaxBalanceDecrease { account_index, max_decrease });
+            }
+            for arg in &max_token_decrease {
+                let (account_index, max_decrease) = parse_index_amount(arg)?;
+                post_conditions.push(PostCondition::MaxDataDecrease {
+                    account_index,
+                    offset: TOKEN_HOLDING_BALANCE_OFFSET,
+                    max_decrease,
+                });
+            }
+            for account_index in must_exist {
+                post_conditions.push(PostCondition::AccountExists { account_index });
+            }
+            for condition in &post_conditions {
+                say!(cx, "   Check after:  {:?}", condition);
+            }
+            print_memo(cx, &memo, content_hash.as_ref());
 
             let instruction = Instruction::Propose {
                 target_program_id,
                 target_instruction_data,
-                target_account_count,
+                target_account_ids: target_account_ids.iter().map(|id| *id.value()).collect(),
                 pda_seeds,
                 authorized_indices: authorized_index,
+                memo,
+                content_hash,
+                draft,
+                depends_on,
+                recurrence,
+                post_conditions,
+                expiry,
             };
 
-            submit_signed_tx(
-                &wallet_core, program_id,
+            let proposal_index = propose_with_retry(
+                cx, &wallet_core, program_id, &ck, account_id, instruction, "Propose",
+            ).await?;
+
+            if draft {
+                say!(cx, "\n💡 Co-signers can review it with:");
+                say!(cx, "   multisig proposal show --multisig {} -i {} --raw", multisig, proposal_index);
+                say!(cx, "   Open it for voting with: multisig activate --multisig {} -i {} --account {}", multisig, proposal_index, account_id);
+            }
+        }
+
+        // ── Transfer ────────────────────────────────────────────────────
+        //
+        // A Propose whose ChainedCall is the token program's Transfer from the
+        // vault, with the vault authorized via its PDA seed.
+        Commands::Transfer {
+            multisig,
+            account,
+            vault,
+            to,
+            amount,
+            token_program,
+            token_program_id,
+            cap_vault_decrease,
+            memo,
+            content_hash,
+        } => {
+            let ck = config.create_key(&multisig)?;
+            let account_id = config.account(&multisig, account)?;
+            let vault_pda = compute_vault_pda(&program_id, &ck);
+            let vault_id = match vault {
+                Some(vault) => parse_account_id(&vault, "vault account")?,
+                None => vault_pda,
+            };
+            if vault_id != vault_pda {
+                cx.warn(format!("{} is not this multisig's vault PDA ({}) — the token program will not accept the multisig's authorization", vault_id, vault_pda));
+            }
+            let recipient_id = parse_account_id(&to, "recipient account")?;
+            let token_program_id = match (token_program, token_program_id, saved_token_program_id) {
+                (_, Some(id), _) => parse_program_id(&id)?,
+                (Some(path), None, _) => load_program(&path)?.1,
+                (None, None, Some(id)) => id,
+                (None, None, None) => {
+                    return Err("No token program: pass --token-program or --token-program-id, or save one with `config add --token-program-id`".to_string());
+                }
+            };
+
+            let memo = memo.unwrap_or_else(|| format!("Transfer {} to {}", amount, recipient_id));
+            let content_hash = parse_memo(&memo, content_hash.as_deref())?;
+
+            // Whatever the token program does, the vault loses at most `amount`
+            let post_conditions: Vec<PostCondition> = cap_vault_decrease
+                .then_some(PostCondition::MaxDataDecrease {
+                    account_index: 0,
+                    offset: TOKEN_HOLDING_BALANCE_OFFSET,
+                    max_decrease: amount,
+                })
+                .into_iter()
+                .collect();
+
+            let transfer = TokenInstruction::Transfer { amount_to_transfer: amount };
+            let instruction = Instruction::Propose {
+                target_program_id: token_program_id,
+                target_instruction_data: risc0_zkvm::serde::to_vec(&transfer).unwrap(),
+                target_account_ids: vec![*vault_id.value(), *recipient_id.value()],
+                pda_seeds: vec![vault_pda_seed_bytes(&ck)],
+                authorized_indices: vec![0], // the vault pays
+                memo: memo.clone(),
+                content_hash,
+                draft: false,
+                depends_on: None,
+                recurrence: None,
+                post_conditions: post_conditions.clone(),
+                expiry: None,
+            };
+
+            say!(cx, "💸 Proposing transfer of {} tokens...", amount);
+            say!(cx, "   From vault:   {}", vault_id);
+            say!(cx, "   To:           {}", recipient_id);
+            say!(cx, "   Token program: {}", program_id_hex(&token_program_id));
+            for condition in &post_conditions {
+                say!(cx, "   Check after:  {:?}", condition);
+            }
+            print_memo(cx, &memo, content_hash.as_ref());
+
+            propose_with_retry(cx, &wallet_core, program_id, &ck, account_id, instruction, "Propose").await?;
+        }
+
+        // ── Activate ────────────────────────────────────────────────────
+        //
+        // Account layout: [state_pda, proposer, proposal_pda]
+        // Proposer is the signer.
+        Commands::Activate { multisig, index, account } => {
+            let ck = config.create_key(&multisig)?;
+            let multisig_state_id = compute_multisig_state_pda(&program_id, &ck);
+            let account_id = config.account(&multisig, account)?;
+            let proposal_pda = compute_proposal_pda(&program_id, &ck, index);
+
+            say!(cx, "🚦 Activating draft proposal #{}...", index);
+            say!(cx, "   State PDA:    {}", multisig_state_id);
+            cx.record("state_pda", multisig_state_id.to_string());
+            say!(cx, "   Proposer:     {}", account_id);
+            say!(cx, "   Proposal PDA: {}", proposal_pda);
+            cx.record_proposal(index, proposal_pda);
+
+            let outcome = submit_signed_tx(
+                cx, &wallet_core, program_id,
                 vec![multisig_state_id, account_id, proposal_pda],
                 account_id,
-                instruction,
-                "Propose",
-            ).await;
+                Instruction::ActivateProposal { proposal_index: index },
+                "ActivateProposal",
+            ).await?;
+            cx.record_outcome(outcome);
+        }
+
+        // ── Expire ──────────────────────────────────────────────────────
+        //
+        // Account layout: [state_pda, proposal_pda, clock]
+        // No signer: the clock decides whether the deadline has passed.
+        Commands::Expire { multisig, index } => {
+            if cx.unsigned_out.is_some() {
+                return Err("expire needs no signature; --unsigned-out is not supported, submit it directly".to_string());
+            }
+            let ck = config.create_key(&multisig)?;
+            let multisig_state_id = compute_multisig_state_pda(&program_id, &ck);
+            let proposal_pda = compute_proposal_pda(&program_id, &ck, index);
+            let proposal = try_fetch_proposal(&wallet_core, proposal_pda).await?
+                .ok_or_else(|| format!("No proposal found at {}", proposal_pda))?;
+            let expiry = proposal.expiry
+                .ok_or_else(|| format!("Proposal #{} has no expiry", index))?;
+            let clock_id = AccountId::new(expiry.clock_account_id);
+
+            say!(cx, "⌛ Expiring proposal #{}...", index);
+            say!(cx, "   State PDA:    {}", multisig_state_id);
+            cx.record("state_pda", multisig_state_id.to_string());
+            say!(cx, "   Proposal PDA: {}", proposal_pda);
+            cx.record_proposal(index, proposal_pda);
+            say!(cx, "   Expires at:   {} (clock {})", expiry.expires_at, clock_id);
+
+            let instruction = Instruction::ExpireProposal { proposal_index: index };
+            let account_ids = vec![multisig_state_id, proposal_pda, clock_id];
+
+            let outcome = if cx.dry_run {
+                dry_run(cx, &wallet_core, &account_ids, None, &instruction, "ExpireProposal").await?
+            } else {
+                let message = Message::try_new(
+                    program_id,
+                    account_ids,
+                    vec![],
+                    instruction,
+                ).map_err(|e| format!("Failed to build ExpireProposal message: {:?}", e))?;
+                let witness_set = WitnessSet::for_message(&message, &[] as &[&nssa::PrivateKey]);
+                let tx = PublicTransaction::new(message, witness_set);
+                submit_and_confirm(cx, &wallet_core, tx, "ExpireProposal").await?
+            };
+            cx.record_outcome(outcome);
+        }
+
+        // ── Proposal list / show ────────────────────────────────────────
+        //
+        // Read-only: proposals live at compute_proposal_pda(1..=transaction_index).
+        Commands::Proposal(ProposalCommand::List { multisig, pending }) => {
+            let ck = config.create_key(&multisig)?;
+            let multisig_state_id = compute_multisig_state_pda(&program_id, &ck);
+            let state = try_fetch_state(&wallet_core, multisig_state_id).await?
+                .ok_or_else(|| format!("No multisig found at {}", multisig_state_id))?;
+
+            say!(cx, "📋 Proposals of {} ({}-of-{})", multisig, state.threshold, state.member_count);
+            cx.record("proposals", Value::Array(vec![]));
+            for index in 1..=state.transaction_index {
+                let proposal_pda = compute_proposal_pda(&program_id, &ck, index);
+                let Some(proposal) = try_fetch_proposal(&wallet_core, proposal_pda).await? else {
+                    say!(cx, "   #{:<4} ⚠️  could not decode {}", index, proposal_pda);
+                    if cx.format != OutputFormat::Text {
+                        cx.warn(format!("Could not decode proposal #{} at {}", index, proposal_pda));
+                    }
+                    continue;
+                };
+                if pending && !matches!(proposal.status, ProposalStatus::Active | ProposalStatus::Draft) {
+                    continue;
+                }
+                cx.record_push("proposals", proposal_json(&proposal, &state, &program_id, saved_token_program_id.as_ref()));
+                say!(
+                    cx,
+                    "   #{:<4} {:<9} {}/{} approvals, {} rejections — {}",
+                    index,
+                    format!("{:?}", proposal.status),
+                    proposal.approval_count(&state),
+                    state.threshold,
+                    proposal.rejection_count(&state),
+                    if proposal.memo.is_empty() { describe_action(&proposal, &program_id) } else { proposal.memo.clone() },
+                );
+            }
+        }
+
+        Commands::Proposal(ProposalCommand::Show { multisig, index, raw }) => {
+            let ck = config.create_key(&multisig)?;
+            show_proposal(cx, &wallet_core, program_id, saved_token_program_id.as_ref(), &ck, index, raw).await?;
+        }
+
+        // ── Inspect ─────────────────────────────────────────────────────
+        //
+        // Read-only: dumps the exact account bytes members are asked to vote on.
+        Commands::Inspect { multisig, index } => {
+            let ck = config.create_key(&multisig)?;
+            show_proposal(cx, &wallet_core, program_id, saved_token_program_id.as_ref(), &ck, index, true).await?;
         }
 
         // ── Approve ─────────────────────────────────────────────────────
         //
         // Account layout: [state_pda, approver, proposal_pda]
+        // With --execute: [state_pda, approver, proposal_pda, ...targets]
         // Approver is the signer.
-        Commands::Approve { multisig, index, account } => {
-            let ck = parse_create_key(&multisig);
+        Commands::Approve { multisig, index, account, execute, post_states } => {
+            let ck = config.create_key(&multisig)?;
             let multisig_state_id = compute_multisig_state_pda(&program_id, &ck);
-            let account_id: AccountId = account.parse().expect("Invalid account ID");
+            let account_id = config.account(&multisig, account)?;
             let proposal_pda = compute_proposal_pda(&program_id, &ck, index);
 
-            println!("👍 Approving proposal #{}...", index);
-            println!("   State PDA:    {}", multisig_state_id);
-            println!("   Approver:     {}", account_id);
-            println!("   Proposal PDA: {}", proposal_pda);
-
-            submit_signed_tx(
-                &wallet_core, program_id,
-                vec![multisig_state_id, account_id, proposal_pda],
-                account_id,
-                Instruction::Approve { proposal_index: index },
-                "Approve",
-            ).await;
+            say!(cx, "👍 Approving proposal #{}...", index);
+            say!(cx, "   State PDA:    {}", multisig_state_id);
+            cx.record("state_pda", multisig_state_id.to_string());
+            say!(cx, "   Approver:     {}", account_id);
+            say!(cx, "   Proposal PDA: {}", proposal_pda);
+            cx.record_proposal(index, proposal_pda);
+
+            // With --execute the target accounts (and any dependency) recorded in the
+            // proposal are passed along, so the program can execute immediately if
+            // threshold is reached.
+            let vote = if execute { Vote::ApproveAndExecute } else { Vote::Approve };
+            let outcome = submit_vote(cx, &wallet_core, program_id, &ck, index, account_id, vote, None, &post_states).await?;
+            cx.record_outcome(outcome);
         }
 
         // ── Reject ──────────────────────────────────────────────────────
@@ -450,142 +2231,298 @@ async fn main() {
         // Account layout: [state_pda, rejector, proposal_pda]
         // Rejector is the signer.
         Commands::Reject { multisig, index, account } => {
-            let ck = parse_create_key(&multisig);
+            let ck = config.create_key(&multisig)?;
             let multisig_state_id = compute_multisig_state_pda(&program_id, &ck);
-            let account_id: AccountId = account.parse().expect("Invalid account ID");
+            let account_id = config.account(&multisig, account)?;
             let proposal_pda = compute_proposal_pda(&program_id, &ck, index);
 
-            println!("👎 Rejecting proposal #{}...", index);
-            println!("   State PDA:    {}", multisig_state_id);
-            println!("   Rejector:     {}", account_id);
-            println!("   Proposal PDA: {}", proposal_pda);
-
-            submit_signed_tx(
-                &wallet_core, program_id,
-                vec![multisig_state_id, account_id, proposal_pda],
-                account_id,
-                Instruction::Reject { proposal_index: index },
-                "Reject",
-            ).await;
+            say!(cx, "👎 Rejecting proposal #{}...", index);
+            say!(cx, "   State PDA:    {}", multisig_state_id);
+            cx.record("state_pda", multisig_state_id.to_string());
+            say!(cx, "   Rejector:     {}", account_id);
+            say!(cx, "   Proposal PDA: {}", proposal_pda);
+            cx.record_proposal(index, proposal_pda);
+
+            let outcome = submit_vote(
+                cx, &wallet_core, program_id, &ck, index, account_id, Vote::Reject, None, &PostStateArgs::default(),
+            ).await?;
+            cx.record_outcome(outcome);
         }
 
         // ── Execute ─────────────────────────────────────────────────────
         //
-        // Account layout: [state_pda, executor, proposal_pda]
-        // Executor is the signer. Target accounts are handled by ChainedCall
-        // inside the program itself — no extra accounts needed in the CLI.
-        Commands::Execute { multisig, index, account } => {
-            let ck = parse_create_key(&multisig);
+        // Account layout: [state_pda, executor, proposal_pda, dependency_pda?, clock?, ...targets]
+        // Executor is the signer. The program passes the target accounts on to
+        // the ChainedCall, so they must be included in the transaction.
+        Commands::Execute { multisig, index, account, target_account, post_states } => {
+            let ck = config.create_key(&multisig)?;
             let multisig_state_id = compute_multisig_state_pda(&program_id, &ck);
-            let account_id: AccountId = account.parse().expect("Invalid account ID");
+            let account_id = config.account(&multisig, account)?;
             let proposal_pda = compute_proposal_pda(&program_id, &ck, index);
 
-            println!("⚡ Executing proposal #{}...", index);
-            println!("   State PDA:    {}", multisig_state_id);
-            println!("   Executor:     {}", account_id);
-            println!("   Proposal PDA: {}", proposal_pda);
-
-            submit_signed_tx(
-                &wallet_core, program_id,
-                vec![multisig_state_id, account_id, proposal_pda],
-                account_id,
-                Instruction::Execute { proposal_index: index },
-                "Execute",
-            ).await;
+            say!(cx, "⚡ Executing proposal #{}...", index);
+            say!(cx, "   State PDA:    {}", multisig_state_id);
+            cx.record("state_pda", multisig_state_id.to_string());
+            say!(cx, "   Executor:     {}", account_id);
+            say!(cx, "   Proposal PDA: {}", proposal_pda);
+            cx.record_proposal(index, proposal_pda);
+
+            let target_ids = target_account.iter()
+                .map(|s| parse_account_id(s, "target account"))
+                .collect::<Result<Vec<_>, _>>()?;
+            let outcome = submit_vote(
+                cx, &wallet_core, program_id, &ck, index, account_id, Vote::Execute,
+                (!target_ids.is_empty()).then_some(target_ids), &post_states,
+            ).await?;
+            cx.record_outcome(outcome);
         }
 
         // ── Add Member ─────────────────────────────────────────────────
-        Commands::AddMember { multisig, account, member } => {
-            let ck = parse_create_key(&multisig);
-            let multisig_state_id = compute_multisig_state_pda(&program_id, &ck);
-            let account_id: AccountId = account.parse().expect("Invalid account ID");
-            let new_member_id: AccountId = member.parse().expect("Invalid member ID");
-
-            // Read current state to get next proposal index
-            let state = wallet_core
-                .sequencer_client
-                .get_account(multisig_state_id)
-                .await
-                .expect("Failed to get multisig state");
-            let state_data: Vec<u8> = state.account.data.into();
-            let ms_state: multisig_core::MultisigState = borsh::from_slice(&state_data)
-                .expect("Failed to deserialize multisig state");
-            let proposal_index = ms_state.transaction_index + 1;
-            let proposal_pda = compute_proposal_pda(&program_id, &ck, proposal_index);
-
-            println!("➕ Proposing add member...");
-            println!("   New member:   {}", new_member_id);
-            println!("   Proposal #{}  PDA: {}", proposal_index, proposal_pda);
-
-            submit_signed_tx(
-                &wallet_core, program_id,
-                vec![multisig_state_id, account_id, proposal_pda],
-                account_id,
-                Instruction::ProposeAddMember { new_member: *new_member_id.value() },
+        Commands::AddMember { multisig, account, member, memo, content_hash } => {
+            let ck = config.create_key(&multisig)?;
+            let account_id = config.account(&multisig, account)?;
+            let new_member_id = parse_account_id(&member, "member")?;
+            let content_hash = parse_memo(&memo, content_hash.as_deref())?;
+
+            say!(cx, "➕ Proposing add member...");
+            say!(cx, "   New member:   {}", new_member_id);
+            print_memo(cx, &memo, content_hash.as_ref());
+
+            propose_with_retry(
+                cx, &wallet_core, program_id, &ck, account_id,
+                Instruction::ProposeAddMember {
+                    new_member: *new_member_id.value(),
+                    memo,
+                    content_hash,
+                },
                 "ProposeAddMember",
-            ).await;
+            ).await?;
         }
 
         // ── Remove Member ───────────────────────────────────────────────
-        Commands::RemoveMember { multisig, account, member } => {
-            let ck = parse_create_key(&multisig);
-            let multisig_state_id = compute_multisig_state_pda(&program_id, &ck);
-            let account_id: AccountId = account.parse().expect("Invalid account ID");
-            let member_id: AccountId = member.parse().expect("Invalid member ID");
-
-            let state = wallet_core
-                .sequencer_client
-                .get_account(multisig_state_id)
-                .await
-                .expect("Failed to get multisig state");
-            let state_data: Vec<u8> = state.account.data.into();
-            let ms_state: multisig_core::MultisigState = borsh::from_slice(&state_data)
-                .expect("Failed to deserialize multisig state");
-            let proposal_index = ms_state.transaction_index + 1;
-            let proposal_pda = compute_proposal_pda(&program_id, &ck, proposal_index);
-
-            println!("➖ Proposing remove member...");
-            println!("   Member:       {}", member_id);
-            println!("   Proposal #{}  PDA: {}", proposal_index, proposal_pda);
-
-            submit_signed_tx(
-                &wallet_core, program_id,
-                vec![multisig_state_id, account_id, proposal_pda],
-                account_id,
-                Instruction::ProposeRemoveMember { member: *member_id.value() },
+        Commands::RemoveMember { multisig, account, member, memo, content_hash } => {
+            let ck = config.create_key(&multisig)?;
+            let account_id = config.account(&multisig, account)?;
+            let member_id = parse_account_id(&member, "member")?;
+            let content_hash = parse_memo(&memo, content_hash.as_deref())?;
+
+            say!(cx, "➖ Proposing remove member...");
+            say!(cx, "   Member:       {}", member_id);
+            print_memo(cx, &memo, content_hash.as_ref());
+
+            propose_with_retry(
+                cx, &wallet_core, program_id, &ck, account_id,
+                Instruction::ProposeRemoveMember {
+                    member: *member_id.value(),
+                    memo,
+                    content_hash,
+                },
                 "ProposeRemoveMember",
-            ).await;
+            ).await?;
         }
 
         // ── Change Threshold ────────────────────────────────────────────
-        Commands::ChangeThreshold { multisig, account, threshold } => {
-            let ck = parse_create_key(&multisig);
+        Commands::ChangeThreshold { multisig, account, threshold, memo, content_hash } => {
+            let ck = config.create_key(&multisig)?;
+            let account_id = config.account(&multisig, account)?;
+            let content_hash = parse_memo(&memo, content_hash.as_deref())?;
+
+            say!(cx, "🔧 Proposing change threshold to {}...", threshold);
+            print_memo(cx, &memo, content_hash.as_ref());
+
+            propose_with_retry(
+                cx, &wallet_core, program_id, &ck, account_id,
+                Instruction::ProposeChangeThreshold {
+                    new_threshold: threshold,
+                    memo,
+                    content_hash,
+                },
+                "ProposeChangeThreshold",
+            ).await?;
+        }
+
+        // ── Propose Nested Vote ─────────────────────────────────────────
+        //
+        // Creates a child proposal whose ChainedCall invokes the parent's
+        // Approve/Reject with the child vault PDA as the (authorized) voter.
+        Commands::ProposeNestedVote { multisig, account, parent, parent_index, reject, memo, content_hash } => {
+            let ck = config.create_key(&multisig)?;
+            let parent_ck = parse_create_key(&parent)?;
+            let account_id = config.account(&multisig, account)?;
+            let content_hash = parse_memo(&memo, content_hash.as_deref())?;
+
+            let vote = if reject {
+                Instruction::Reject { proposal_index: parent_index }
+            } else {
+                Instruction::Approve { proposal_index: parent_index }
+            };
+            let target_instruction_data = risc0_zkvm::serde::to_vec(&vote)
+                .expect("Failed to serialize parent vote instruction");
+
+            say!(cx, "🗳️  Proposing to {} parent proposal #{}...", if reject { "reject" } else { "approve" }, parent_index);
+            say!(cx, "   Parent:       {}", AccountId::new(parent_ck));
+            say!(cx, "   Voter (vault): {}", compute_vault_pda(&program_id, &ck));
+            print_memo(cx, &memo, content_hash.as_ref());
+
+            propose_with_retry(
+                cx, &wallet_core, program_id, &ck, account_id,
+                Instruction::Propose {
+                    target_program_id: program_id,
+                    target_instruction_data,
+                    target_account_ids: nested_vote_target_account_ids(&program_id, &parent_ck, parent_index, &ck),
+                    pda_seeds: vec![vault_pda_seed_bytes(&ck)],
+                    authorized_indices: vec![NESTED_VOTE_VOTER_INDEX],
+                    memo,
+                    content_hash,
+                    draft: false,
+                    depends_on: None,
+                    recurrence: None,
+                    post_conditions: vec![],
+                    expiry: None,
+                },
+                "Propose",
+            ).await?;
+        }
+
+        // ── Set Permissionless Execution ────────────────────────────────
+        Commands::SetPermissionlessExecution { multisig, account, enabled, memo, content_hash } => {
+            let ck = config.create_key(&multisig)?;
+            let account_id = config.account(&multisig, account)?;
+            let content_hash = parse_memo(&memo, content_hash.as_deref())?;
+
+            say!(cx, "🔓 Proposing {} permissionless execution...", if enabled { "enabling" } else { "disabling" });
+            print_memo(cx, &memo, content_hash.as_ref());
+
+            propose_with_retry(
+                cx, &wallet_core, program_id, &ck, account_id,
+                Instruction::ProposeSetPermissionlessExecution {
+                    enabled,
+                    memo,
+                    content_hash,
+                },
+                "ProposeSetPermissionlessExecution",
+            ).await?;
+        }
+
+        // ── Set Reject Threshold ────────────────────────────────────────
+        Commands::SetRejectThreshold { multisig, account, cutoff, memo, content_hash } => {
+            let ck = config.create_key(&multisig)?;
             let multisig_state_id = compute_multisig_state_pda(&program_id, &ck);
-            let account_id: AccountId = account.parse().expect("Invalid account ID");
-
-            let state = wallet_core
-                .sequencer_client
-                .get_account(multisig_state_id)
-                .await
-                .expect("Failed to get multisig state");
-            let state_data: Vec<u8> = state.account.data.into();
-            let ms_state: multisig_core::MultisigState = borsh::from_slice(&state_data)
-                .expect("Failed to deserialize multisig state");
-            let proposal_index = ms_state.transaction_index + 1;
-            let proposal_pda = compute_proposal_pda(&program_id, &ck, proposal_index);
-
-            println!("🔧 Proposing change threshold to {}...", threshold);
-            println!("   Proposal #{}  PDA: {}", proposal_index, proposal_pda);
-
-            submit_signed_tx(
-                &wallet_core, program_id,
-                vec![multisig_state_id, account_id, proposal_pda],
+            let account_id = config.account(&multisig, account)?;
+            let content_hash = parse_memo(&memo, content_hash.as_deref())?;
+            let state = try_fetch_state(&wallet_core, multisig_state_id).await?
+                .ok_or_else(|| format!("No multisig found at {}", multisig_state_id))?;
+
+            match cutoff {
+                Some(n) => say!(cx, "🛑 Proposing reject threshold of {} (of {} members)...", n, state.member_count),
+                None => say!(cx, "🛑 Proposing to clear the reject threshold..."),
+            }
+            print_memo(cx, &memo, content_hash.as_ref());
+
+            propose_with_retry(
+                cx, &wallet_core, program_id, &ck, account_id,
+                Instruction::ProposeSetRejectThreshold {
+                    reject_threshold: cutoff,
+                    memo,
+                    content_hash,
+                },
+                "ProposeSetRejectThreshold",
+            ).await?;
+        }
+
+        // ── Migrate ─────────────────────────────────────────────────────
+        //
+        // Account layout: [state_pda, member, proposal_pda...]
+        // Member is the signer.
+        Commands::Migrate { multisig, account, proposal } => {
+            let ck = config.create_key(&multisig)?;
+            let multisig_state_id = compute_multisig_state_pda(&program_id, &ck);
+            let account_id = config.account(&multisig, account)?;
+
+            say!(cx, "🔄 Migrating multisig accounts to the current schema...");
+            say!(cx, "   State PDA:    {}", multisig_state_id);
+            cx.record("state_pda", multisig_state_id.to_string());
+
+            let mut account_ids = vec![multisig_state_id, account_id];
+            for index in &proposal {
+                let proposal_pda = compute_proposal_pda(&program_id, &ck, *index);
+                say!(cx, "   Proposal #{}  PDA: {}", index, proposal_pda);
+                cx.record_push("proposal_pdas", proposal_pda.to_string());
+                account_ids.push(proposal_pda);
+            }
+
+            let outcome = submit_signed_tx(
+                cx, &wallet_core, program_id,
+                account_ids,
                 account_id,
-                Instruction::ProposeChangeThreshold { new_threshold: threshold },
-                "ProposeChangeThreshold",
-            ).await;
+                Instruction::MigrateState,
+                "MigrateState",
+            ).await?;
+            cx.record_outcome(outcome);
+        }
+
+        // ── Info ────────────────────────────────────────────────────────
+        //
+        // Read-only: decodes the state PDA and looks up the vault.
+        Commands::Info { multisig } => {
+            let ck = config.create_key(&multisig)?;
+            let multisig_state_id = compute_multisig_state_pda(&program_id, &ck);
+            let vault_id = compute_vault_pda(&program_id, &ck);
+
+            let state = try_fetch_state(&wallet_core, multisig_state_id).await?
+                .ok_or_else(|| format!("No multisig found at {}", multisig_state_id))?;
+
+            say!(cx, "📊 Multisig {}", multisig);
+            cx.record("state", state_json(&state));
+            cx.record("program_id", program_id_hex(&program_id));
+            cx.record("vault_pda", vault_id.to_string());
+            say!(cx, "   Program ID:   {:?}", program_id);
+            say!(cx, "   State PDA:    {}", multisig_state_id);
+            cx.record("state_pda", multisig_state_id.to_string());
+            say!(cx, "   Threshold:    {}-of-{}", state.threshold, state.member_count);
+            for (i, member) in state.members.iter().enumerate() {
+                say!(cx, "   Member #{}:    {}", i, AccountId::new(*member));
+            }
+            say!(cx, "   Proposals:    {} (next index {})", state.transaction_index, state.transaction_index + 1);
+            if let Some(cutoff) = state.reject_threshold {
+                say!(cx, "   Reject at:    {} rejections", cutoff);
+            }
+            if state.permissionless_execution {
+                say!(cx, "   Execution:    permissionless");
+            }
+            if let Some(new_program_id) = &state.migrated_to {
+                say!(cx, "   Migrated to:  {} (no further proposals, votes or executions)", program_id_hex(new_program_id));
+            }
+            say!(cx, "   Vault PDA:    {}", vault_id);
+            match wallet_core.sequencer_client.get_account(vault_id).await {
+                Ok(vault) => {
+                    say!(cx, "   Vault balance: {}", vault.account.balance);
+                    // u128 does not fit a JSON number
+                    cx.record("vault_balance", vault.account.balance.to_string());
+                }
+                Err(e) => cx.warn(format!("Failed to fetch vault: {:?}", e)),
+            }
         }
 
-        Commands::Completions { .. } | Commands::Status => unreachable!(),
+        Commands::Completions { .. } | Commands::Sign { .. } | Commands::Submit { .. } | Commands::Config(_) | Commands::Tui { .. } => unreachable!(),
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use token_core::TokenHolding;
+
+    #[test]
+    fn test_token_holding_balance_offset() {
+        let balance: u128 = 0x0102_0304_0506_0708_090a_0b0c_0d0e_0f10;
+        let holding = TokenHolding::Fungible { definition_id: AccountId::new([7u8; 32]), balance };
+        let data = borsh::to_vec(&holding).unwrap();
+
+        let offset = TOKEN_HOLDING_BALANCE_OFFSET as usize;
+        assert_eq!(data.len(), offset + 16);
+        assert_eq!(data[offset..offset + 16], balance.to_le_bytes());
     }
 }
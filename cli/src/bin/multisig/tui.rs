@@ -0,0 +1,351 @@
+// `multisig tui` — review and vote on pending proposals interactively.
+//
+// Shows the saved multisigs as tabs, the open proposals of the selected one
+// with their decoded action and vote tally, and submits approve / reject /
+// execute for the highlighted proposal through the same code as the
+// `approve`, `reject` and `execute` commands. Each vote asks for confirmation
+// first.
+//
+// While the TUI owns the terminal, votes are submitted with text output
+// switched off and their outcome is shown in the status line instead.
+
+use super::*;
+use multisig_core::{MultisigState, Proposal};
+use ratatui::{
+    DefaultTerminal, Frame,
+    crossterm::event::{self, Event, KeyCode, KeyEventKind},
+    layout::{Constraint, Layout},
+    style::{Modifier, Style},
+    text::{Line, Text},
+    widgets::{Block, List, ListItem, ListState, Paragraph, Tabs, Wrap},
+};
+
+/// A saved multisig, resolved for voting.
+pub(super) struct TuiMultisig {
+    pub name: String,
+    pub create_key: [u8; 32],
+    pub program_id: nssa::ProgramId,
+    /// Token program whose calls are decoded, if saved with the multisig
+    pub token_program_id: Option<nssa::ProgramId>,
+    /// Member account to vote with; without one the TUI is read-only
+    pub account: Option<AccountId>,
+    pub wallet: WalletCore,
+}
+
+/// On-chain state of one multisig, as last loaded.
+struct View {
+    /// The multisig, `None` if there is none, or why it could not be loaded
+    state: Result<Option<MultisigState>, String>,
+    /// Open (Active or Draft) proposals, oldest first
+    proposals: Vec<Proposal>,
+}
+
+struct App {
+    multisigs: Vec<TuiMultisig>,
+    /// Loaded views, by multisig; `None` until first shown
+    views: Vec<Option<View>>,
+    selected: usize,
+    list: ListState,
+    /// Vote waiting for y/n
+    confirm: Option<Vote>,
+    status: String,
+}
+
+const HELP: &str = "←/→ multisig  ↑/↓ proposal  a approve  r reject  x execute  f refresh  q quit";
+
+pub(super) async fn run(multisigs: Vec<TuiMultisig>) -> Result<(), String> {
+    let mut app = App {
+        views: multisigs.iter().map(|_| None).collect(),
+        multisigs,
+        selected: 0,
+        list: ListState::default(),
+        confirm: None,
+        status: String::new(),
+    };
+
+    let mut terminal = ratatui::init();
+    let result = app.run(&mut terminal).await;
+    ratatui::restore();
+
+    result.map_err(|e| format!("Terminal error: {}", e))
+}
+
+impl App {
+    async fn run(&mut self, terminal: &mut DefaultTerminal) -> std::io::Result<()> {
+        self.reload(terminal).await?;
+        loop {
+            terminal.draw(|frame| self.draw(frame))?;
+            // `event::read` blocks; keep it off the async runtime's worker
+            let event = tokio::task::spawn_blocking(event::read).await.map_err(std::io::Error::other)??;
+            let Event::Key(key) = event else { continue };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+
+            if let Some(vote) = self.confirm.take() {
+                if matches!(key.code, KeyCode::Char('y') | KeyCode::Enter) {
+                    self.status = format!("⏳ Submitting {}, waiting for confirmation...", vote.label());
+                    terminal.draw(|frame| self.draw(frame))?;
+                    self.submit(vote).await;
+                    self.reload(terminal).await?;
+                } else {
+                    self.status = "Cancelled".to_string();
+                }
+                continue;
+            }
+
+            match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                KeyCode::Right | KeyCode::Tab | KeyCode::Char('l') => {
+                    self.selected = (self.selected + 1) % self.multisigs.len();
+                    self.show_selected(terminal).await?;
+                }
+                KeyCode::Left | KeyCode::BackTab | KeyCode::Char('h') => {
+                    self.selected = (self.selected + self.multisigs.len() - 1) % self.multisigs.len();
+                    self.show_selected(terminal).await?;
+                }
+                KeyCode::Down | KeyCode::Char('j') => self.list.select_next(),
+                KeyCode::Up | KeyCode::Char('k') => self.list.select_previous(),
+                KeyCode::Char('f') => {
+                    self.status.clear();
+                    self.reload(terminal).await?;
+                }
+                KeyCode::Char('a') => self.ask(Vote::Approve),
+                KeyCode::Char('r') => self.ask(Vote::Reject),
+                KeyCode::Char('x') => self.ask(Vote::Execute),
+                _ => {}
+            }
+        }
+    }
+
+    /// Switch to the selected multisig, loading it on first view.
+    async fn show_selected(&mut self, terminal: &mut DefaultTerminal) -> std::io::Result<()> {
+        self.status.clear();
+        self.list.select(Some(0));
+        if self.views[self.selected].is_none() {
+            self.reload(terminal).await?;
+        }
+        Ok(())
+    }
+
+    /// (Re)load the selected multisig and its open proposals from chain.
+    async fn reload(&mut self, terminal: &mut DefaultTerminal) -> std::io::Result<()> {
+        self.views[self.selected] = None;
+        terminal.draw(|frame| self.draw(frame))?;
+
+        let multisig = &self.multisigs[self.selected];
+        let state_pda = compute_multisig_state_pda(&multisig.program_id, &multisig.create_key);
+        let mut state = try_fetch_state(&multisig.wallet, state_pda).await;
+        let mut proposals = Vec::new();
+        let count = match &state { Ok(Some(state)) => state.transaction_index, _ => 0 };
+        for index in 1..=count {
+            let proposal_pda = compute_proposal_pda(&multisig.program_id, &multisig.create_key, index);
+            match try_fetch_proposal(&multisig.wallet, proposal_pda).await {
+                Ok(Some(proposal)) if matches!(proposal.status, ProposalStatus::Active | ProposalStatus::Draft) => {
+                    proposals.push(proposal);
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    state = Err(e);
+                    proposals.clear();
+                    break;
+                }
+            }
+        }
+
+        let selected = self.list.selected().unwrap_or(0).min(proposals.len().saturating_sub(1));
+        self.list.select((!proposals.is_empty()).then_some(selected));
+        self.views[self.selected] = Some(View { state, proposals });
+        Ok(())
+    }
+
+    fn selected_proposal(&self) -> Option<(&MultisigState, &Proposal)> {
+        let view = self.views[self.selected].as_ref()?;
+        Some((view.state.as_ref().ok()?.as_ref()?, view.proposals.get(self.list.selected()?)?))
+    }
+
+    /// Check that `vote` makes sense for the highlighted proposal, then ask for confirmation.
+    fn ask(&mut self, vote: Vote) {
+        let Some((state, proposal)) = self.selected_proposal() else {
+            self.status = "No proposal selected".to_string();
+            return;
+        };
+        let Some(account) = self.multisigs[self.selected].account else {
+            self.status = "No account to vote with — pass --account or save one with `multisig config add`".to_string();
+            return;
+        };
+        let member = *account.value();
+
+        let problem = match vote {
+            _ if proposal.status == ProposalStatus::Draft => Some("it is still a draft".to_string()),
+            Vote::Execute if !state.can_execute(&member) => Some(format!("{} may not execute proposals", account)),
+            Vote::Execute if !proposal.has_threshold(state) => Some(format!(
+                "it has {} of {} approvals",
+                proposal.approval_count(state),
+                state.threshold,
+            )),
+            Vote::Execute if !proposal.post_conditions.is_empty() => Some(
+                "it has post-conditions; run `multisig execute` with --post-balance/--post-token-balance".to_string(),
+            ),
+            Vote::Execute => None,
+            _ if !state.is_member(&member) => Some(format!("{} is not a member", account)),
+            _ if proposal.approved.contains(&member) || proposal.rejected.contains(&member) => {
+                Some("you have already voted".to_string())
+            }
+            _ => None,
+        };
+        if let Some(problem) = problem {
+            self.status = format!("Cannot {} proposal #{}: {}", vote.label().to_lowercase(), proposal.index, problem);
+            return;
+        }
+
+        self.status = format!("{} proposal #{} as {}? [y/N]", vote.label(), proposal.index, account);
+        self.confirm = Some(vote);
+    }
+
+    async fn submit(&mut self, vote: Vote) {
+        let Some((_, proposal)) = self.selected_proposal() else { return };
+        let index = proposal.index;
+        let multisig = &self.multisigs[self.selected];
+        let Some(account) = multisig.account else { return };
+
+        let mut cx = Ctx::new(OutputFormat::Silent);
+        let result = submit_vote(
+            &mut cx, &multisig.wallet, multisig.program_id, &multisig.create_key, index, account, vote, None,
+            &PostStateArgs::default(),
+        ).await;
+
+        self.status = match result {
+            Ok(Outcome::Confirmed { tx_hash, .. }) => {
+                format!("✅ {} of proposal #{} confirmed (tx {})", vote.label(), index, tx_hash)
+            }
+            Ok(_) => format!("✅ {} of proposal #{} done", vote.label(), index),
+            Err(e) => format!("❌ {}", e),
+        };
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [tabs_area, body_area, status_area, help_area] = Layout::vertical([
+            Constraint::Length(3),
+            Constraint::Min(0),
+            Constraint::Length(1),
+            Constraint::Length(1),
+        ]).areas(frame.area());
+        let [list_area, detail_area] = Layout::horizontal([
+            Constraint::Percentage(45),
+            Constraint::Percentage(55),
+        ]).areas(body_area);
+        let highlight = Style::new().add_modifier(Modifier::REVERSED);
+
+        let tabs = Tabs::new(self.multisigs.iter().map(|m| m.name.as_str()))
+            .select(self.selected)
+            .highlight_style(highlight)
+            .block(Block::bordered().title(" Multisigs "));
+        frame.render_widget(tabs, tabs_area);
+        frame.render_widget(Line::from(self.status.as_str()), status_area);
+        frame.render_widget(Line::from(HELP).style(Style::new().add_modifier(Modifier::DIM)), help_area);
+
+        let multisig = &self.multisigs[self.selected];
+        let Some(view) = &self.views[self.selected] else {
+            frame.render_widget(Paragraph::new("Loading...").block(Block::bordered()), body_area);
+            return;
+        };
+        let state = match &view.state {
+            Ok(Some(state)) => state,
+            Ok(None) => {
+                let state_pda = compute_multisig_state_pda(&multisig.program_id, &multisig.create_key);
+                frame.render_widget(
+                    Paragraph::new(format!("No multisig found at {}", state_pda)).block(Block::bordered()),
+                    body_area,
+                );
+                return;
+            }
+            Err(e) => {
+                frame.render_widget(
+                    Paragraph::new(format!("❌ {}", e)).wrap(Wrap { trim: false }).block(Block::bordered()),
+                    body_area,
+                );
+                return;
+            }
+        };
+
+        let items: Vec<ListItem> = view.proposals.iter().map(|proposal| {
+            ListItem::new(format!(
+                "#{:<4} {:<6} {}/{} ✓  {} ✗  {}",
+                proposal.index,
+                format!("{:?}", proposal.status),
+                proposal.approval_count(state),
+                state.threshold,
+                proposal.rejection_count(state),
+                if proposal.memo.is_empty() { describe_action(proposal, &multisig.program_id) } else { proposal.memo.clone() },
+            ))
+        }).collect();
+        let title = format!(" Open proposals ({}-of-{}) ", state.threshold, state.member_count);
+        let list = List::new(items)
+            .block(Block::bordered().title(title))
+            .highlight_style(highlight)
+            .highlight_symbol("▶ ");
+        frame.render_stateful_widget(list, list_area, &mut self.list);
+
+        let detail = match self.list.selected().and_then(|i| view.proposals.get(i)) {
+            Some(proposal) => proposal_details(proposal, state, multisig),
+            None => Text::from("No open proposals"),
+        };
+        frame.render_widget(
+            Paragraph::new(detail).wrap(Wrap { trim: false }).block(Block::bordered().title(" Details ")),
+            detail_area,
+        );
+    }
+}
+
+/// Everything a member should see before voting on `proposal`.
+fn proposal_details(proposal: &Proposal, state: &MultisigState, multisig: &TuiMultisig) -> Text<'static> {
+    let mut lines = vec![
+        Line::from(format!("Proposal #{} — {:?}", proposal.index, proposal.status))
+            .style(Style::new().add_modifier(Modifier::BOLD)),
+    ];
+    if !proposal.memo.is_empty() {
+        lines.push(Line::from(format!("Title:      {}", proposal.memo)));
+    }
+    if let Some(hash) = &proposal.content_hash {
+        lines.push(Line::from(format!("Document:   {}", hex::encode(hash))));
+    }
+    lines.push(Line::from(format!("Proposer:   {}", AccountId::new(proposal.proposer))));
+    lines.push(Line::from(format!("Action:     {}", describe_action(proposal, &multisig.program_id))));
+    if proposal.config_action.is_none() {
+        lines.push(Line::from(format!("Call:       {}", describe_instruction(proposal, &multisig.program_id, multisig.token_program_id.as_ref()))));
+        for (i, id) in proposal.target_account_ids.iter().enumerate() {
+            lines.push(Line::from(format!("Target #{}:  {}", i, AccountId::new(*id))));
+        }
+    }
+    if let Some(dependency) = proposal.depends_on {
+        lines.push(Line::from(format!("Requires:   proposal #{} executed first", dependency)));
+    }
+    for condition in &proposal.post_conditions {
+        lines.push(Line::from(format!("Guarantee:  {:?}", condition)));
+    }
+
+    lines.push(Line::from(""));
+    lines.push(Line::from(format!(
+        "Approvals:  {} of {} needed",
+        proposal.approval_count(state),
+        state.threshold,
+    )));
+    lines.extend(proposal.approved.iter().map(|id| Line::from(format!("  ✓ {}", AccountId::new(*id)))));
+    lines.push(Line::from(format!("Rejections: {}", proposal.rejection_count(state))));
+    lines.extend(proposal.rejected.iter().map(|id| Line::from(format!("  ✗ {}", AccountId::new(*id)))));
+
+    if let Some(account) = multisig.account {
+        let member = account.value();
+        let vote = if proposal.approved.contains(member) {
+            "approved"
+        } else if proposal.rejected.contains(member) {
+            "rejected"
+        } else {
+            "not voted yet"
+        };
+        lines.push(Line::from(""));
+        lines.push(Line::from(format!("Your vote ({}): {}", account, vote)));
+    }
+    Text::from(lines)
+}
@@ -95,13 +95,13 @@ async fn get_nonce(client: &SequencerClient, account_id: AccountId) -> u128 {
 async fn get_multisig_state(client: &SequencerClient, state_id: AccountId) -> MultisigState {
     let account = client.get_account(state_id).await.expect("Failed to get multisig state");
     let data: Vec<u8> = account.account.data.into();
-    borsh::from_slice(&data).expect("Failed to deserialize multisig state")
+    MultisigState::from_bytes(&data).expect("Failed to deserialize multisig state")
 }
 
 async fn get_proposal(client: &SequencerClient, proposal_id: AccountId) -> Proposal {
     let account = client.get_account(proposal_id).await.expect("Failed to get proposal");
     let data: Vec<u8> = account.account.data.into();
-    borsh::from_slice(&data).expect("Failed to deserialize proposal")
+    Proposal::from_bytes(&data).expect("Failed to deserialize proposal")
 }
 
 fn deploy_program(bytecode: Vec<u8>) -> (ProgramDeploymentTransaction, nssa::ProgramId) {
@@ -160,7 +160,7 @@ async fn propose_approve_execute_config(
         program_id,
         vec![multisig_state_id, executor_id, proposal_pda],
         vec![nonce],
-        Instruction::Execute { proposal_index },
+        Instruction::Execute { proposal_index, post_states: vec![] },
     ).unwrap();
     let ws = WitnessSet::for_message(&msg, &[proposer_key]);
     submit_tx(client, PublicTransaction::new(msg, ws)).await;
@@ -233,7 +233,11 @@ async fn test_member_management() {
 
     let state = propose_approve_execute_config(
         &client, program_id, &create_key, multisig_state_id,
-        Instruction::ProposeAddMember { new_member: *m4.value() },
+        Instruction::ProposeAddMember {
+            new_member: *m4.value(),
+            memo: "Add member 4".to_string(),
+            content_hash: None,
+        },
         &key1, &[&key2], // proposer=m1, approver=m2
         1,
     ).await;
@@ -246,7 +250,11 @@ async fn test_member_management() {
     println!("\n═══ STEP 3: Change threshold to 3 ═══");
     let state = propose_approve_execute_config(
         &client, program_id, &create_key, multisig_state_id,
-        Instruction::ProposeChangeThreshold { new_threshold: 3 },
+        Instruction::ProposeChangeThreshold {
+            new_threshold: 3,
+            memo: String::new(),
+            content_hash: None,
+        },
         &key1, &[&key2], // still 2-of-4 required for this proposal
         2,
     ).await;
@@ -258,7 +266,11 @@ async fn test_member_management() {
     println!("\n═══ STEP 4: Remove member 4 ═══");
     let state = propose_approve_execute_config(
         &client, program_id, &create_key, multisig_state_id,
-        Instruction::ProposeRemoveMember { member: *m4.value() },
+        Instruction::ProposeRemoveMember {
+            member: *m4.value(),
+            memo: String::new(),
+            content_hash: None,
+        },
         &key1, &[&key2, &key3], // need 3 approvals: m1 + m2 + m3
         3,
     ).await;
@@ -279,7 +291,11 @@ async fn test_member_management() {
         program_id,
         vec![multisig_state_id, m1, proposal_pda],
         vec![nonce],
-        Instruction::ProposeRemoveMember { member: *m3.value() },
+        Instruction::ProposeRemoveMember {
+            member: *m3.value(),
+            memo: String::new(),
+            content_hash: None,
+        },
     ).unwrap();
     let ws = WitnessSet::for_message(&msg, &[&key1]);
     submit_tx(&client, PublicTransaction::new(msg, ws)).await;
@@ -305,7 +321,7 @@ async fn test_member_management() {
         program_id,
         vec![multisig_state_id, m1, proposal_pda],
         vec![nonce],
-        Instruction::Execute { proposal_index: 4 },
+        Instruction::Execute { proposal_index: 4, post_states: vec![] },
     ).unwrap();
     let ws = WitnessSet::for_message(&msg, &[&key1]);
     let failed = submit_tx_expect_failure(&client, PublicTransaction::new(msg, ws)).await;
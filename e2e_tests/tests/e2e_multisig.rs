@@ -25,7 +25,7 @@ use nssa::{
 };
 use nssa_core::program::PdaSeed;
 use multisig_core::{
-    Instruction, MultisigState, Proposal, ProposalStatus,
+    ExpectedAccount, Instruction, MultisigState, PostCondition, Proposal, ProposalStatus,
     compute_multisig_state_pda, vault_pda_seed_bytes, compute_vault_pda,
     compute_proposal_pda,
 };
@@ -75,6 +75,27 @@ async fn submit_tx(client: &SequencerClient, tx: PublicTransaction) {
     }
 }
 
+/// Submit `tx` and check that it is never included in a block.
+async fn submit_tx_expect_rejected(client: &SequencerClient, tx: PublicTransaction) {
+    let response = match client.send_tx_public(tx).await {
+        Ok(response) => response,
+        Err(e) => {
+            println!("  ✅ tx refused by the sequencer: {:?}", e);
+            return;
+        }
+    };
+    let tx_hash = response.tx_hash.clone();
+    println!("  tx_hash: {}", tx_hash);
+
+    tokio::time::sleep(Duration::from_secs(BLOCK_WAIT_SECS * 2)).await;
+    match client.get_transaction_by_hash(tx_hash.clone()).await {
+        Ok(resp) if resp.transaction.is_some() => {
+            panic!("❌ Transaction {} was included but should have been rejected", tx_hash);
+        }
+        _ => println!("  ✅ tx not included"),
+    }
+}
+
 async fn get_nonce(client: &SequencerClient, account_id: AccountId) -> u128 {
     client.get_account(account_id).await
         .map(|r| r.account.nonce)
@@ -94,7 +115,7 @@ async fn get_balance(client: &SequencerClient, account_id: AccountId) -> Option<
 async fn get_multisig_state(client: &SequencerClient, state_id: AccountId) -> MultisigState {
     let account = client.get_account(state_id).await.expect("Failed to get multisig state");
     let data: Vec<u8> = account.account.data.into();
-    borsh::from_slice(&data).expect("Failed to deserialize multisig state")
+    MultisigState::from_bytes(&data).expect("Failed to deserialize multisig state")
 }
 
 async fn get_proposal(client: &SequencerClient, proposal_id: AccountId) -> Proposal {
@@ -109,19 +130,20 @@ async fn get_proposal(client: &SequencerClient, proposal_id: AccountId) -> Propo
     } else {
         println!("  [DEBUG] Proposal raw data (all {} bytes): {:02x?}", data.len(), &data);
     }
-    // Also try to manually read the index field (first u64)
-    if data.len() >= 8 {
-        let index = u64::from_le_bytes(data[0..8].try_into().unwrap());
+    // Also try to manually read the index field (first u64 after the discriminator and version byte)
+    if data.len() >= 17 {
+        println!("  [DEBUG] Schema version: {}", data[8]);
+        let index = u64::from_le_bytes(data[9..17].try_into().unwrap());
         println!("  [DEBUG] Manual index read: {}", index);
     }
-    match borsh::from_slice::<Proposal>(&data) {
+    match Proposal::from_bytes(&data) {
         Ok(p) => {
             println!("  [DEBUG] Proposal deserialized OK! index={}, status={:?}, approved={}", p.index, p.status, p.approved.len());
             p
         }
         Err(e) => {
             // Try to deserialize a MultisigState instead to see if wrong account
-            if let Ok(ms) = borsh::from_slice::<MultisigState>(&data) {
+            if let Ok(ms) = MultisigState::from_bytes(&data) {
                 panic!("Account contains MultisigState (not Proposal)! members={}, threshold={}", ms.members.len(), ms.threshold);
             }
             panic!("Failed to deserialize proposal ({} bytes): {}", data.len(), e);
@@ -275,9 +297,16 @@ async fn test_multisig_token_transfer() {
     let propose_instruction = Instruction::Propose {
         target_program_id: token_program_id,
         target_instruction_data: target_instruction_data.clone(),
-        target_account_count: 2,  // vault_holding + recipient_holding
+        target_account_ids: vec![*vault_id.value(), *recipient_id.value()],
         pda_seeds: vec![vault_seed],
         authorized_indices: vec![0], // vault (index 0) gets is_authorized=true
+        memo: "Pay 200 tokens to recipient".to_string(),
+        content_hash: None,
+        draft: false,
+        depends_on: None,
+        recurrence: None,
+        post_conditions: vec![],
+        expiry: None,
     };
     let msg = Message::try_new(
         multisig_program_id,
@@ -291,6 +320,7 @@ async fn test_multisig_token_transfer() {
     // Verify proposal was created
     let proposal = get_proposal(&client, proposal_id).await;
     assert_eq!(proposal.approved.len(), 1);
+    assert_eq!(proposal.memo, "Pay 200 tokens to recipient");
     
     let state = get_multisig_state(&client, multisig_state_id).await;
     assert_eq!(state.transaction_index, 1, "transaction_index should be incremented");
@@ -328,7 +358,7 @@ async fn test_multisig_token_transfer() {
         multisig_program_id,
         vec![multisig_state_id, m1, proposal_id, vault_id, recipient_id],
         vec![nonce_m1], // Only signer nonces
-        Instruction::Execute { proposal_index: 1 },
+        Instruction::Execute { proposal_index: 1, post_states: vec![] },
     ).unwrap();
     let ws = WitnessSet::for_message(&msg, &[&key1]);
     submit_tx(&client, PublicTransaction::new(msg, ws)).await;
@@ -359,4 +389,176 @@ async fn test_multisig_token_transfer() {
     println!("   - Propose transfer via ChainedCall ✅");
     println!("   - Approve + Execute ✅");
     println!("   - Token balances verified ✅");
-}
\ No newline at end of file
+}
+
+/// Byte offset of the balance in a fungible `TokenHolding`.
+const TOKEN_HOLDING_BALANCE_OFFSET: u32 = 33;
+
+/// The vault's current account with its token balance replaced by `balance`.
+async fn vault_with_balance(client: &SequencerClient, vault_id: AccountId, balance: u128) -> ExpectedAccount {
+    let account = client.get_account(vault_id).await.expect("Failed to get vault").account;
+    let mut expected = ExpectedAccount::from(&account);
+    let offset = TOKEN_HOLDING_BALANCE_OFFSET as usize;
+    expected.data[offset..offset + 16].copy_from_slice(&balance.to_le_bytes());
+    expected
+}
+
+/// A proposal's post-conditions are checked against the `post_states` the
+/// executor passes. The runtime must refuse them unless they are the accounts
+/// as the proposal's call actually left them, so an executor cannot make an
+/// over-spending call pass its cap by claiming a smaller outcome.
+#[tokio::test]
+async fn test_forged_post_states_are_rejected() {
+    let client = sequencer_client();
+
+    // ── Deploy programs ─────────────────────────────────────────────────
+    println!("📦 Deploying programs...");
+    let token_path = std::env::var("TOKEN_PROGRAM")
+        .unwrap_or_else(|_| panic!("TOKEN_PROGRAM env var not set"));
+    let token_bytecode = std::fs::read(&token_path)
+        .unwrap_or_else(|_| panic!("Cannot read token binary at '{}'", token_path));
+    let (token_deploy_tx, token_program_id) = deploy_program(token_bytecode);
+
+    let multisig_path = std::env::var("MULTISIG_PROGRAM")
+        .unwrap_or_else(|_| panic!("MULTISIG_PROGRAM env var not set"));
+    let multisig_bytecode = std::fs::read(&multisig_path)
+        .unwrap_or_else(|_| panic!("Cannot read multisig binary at '{}'", multisig_path));
+    let (multisig_deploy_tx, multisig_program_id) = deploy_program(multisig_bytecode);
+
+    for (name, tx) in [("token", token_deploy_tx), ("multisig", multisig_deploy_tx)] {
+        match client.send_tx_program(tx).await {
+            Ok(r) => {
+                println!("  {} deployed: {}", name, r.tx_hash);
+                tokio::time::sleep(Duration::from_secs(BLOCK_WAIT_SECS)).await;
+            }
+            Err(e) => println!("  {} deploy skipped: {}", name, e),
+        }
+    }
+
+    // ── Token, 1-of-1 multisig and a vault holding 500 ──────────────────
+    println!("\n═══ STEP 1: Set up a funded 1-of-1 multisig ═══");
+    let def_id = account_id_from_key(&PrivateKey::new_os_random());
+    let minter_holding_key = PrivateKey::new_os_random();
+    let minter_holding_id = account_id_from_key(&minter_holding_key);
+    let msg = Message::try_new(
+        token_program_id,
+        vec![def_id, minter_holding_id],
+        vec![],
+        TokenInstruction::NewFungibleDefinition { name: "CapToken".to_string(), total_supply: 1_000_000 },
+    ).unwrap();
+    let ws = WitnessSet::for_message(&msg, &[] as &[&PrivateKey]);
+    submit_tx(&client, PublicTransaction::new(msg, ws)).await;
+
+    let member_key = PrivateKey::new_os_random();
+    let member = account_id_from_key(&member_key);
+    let create_key: [u8; 32] = *account_id_from_key(&PrivateKey::new_os_random()).value();
+    let multisig_state_id = compute_multisig_state_pda(&multisig_program_id, &create_key);
+    let vault_id = compute_vault_pda(&multisig_program_id, &create_key);
+    let msg = Message::try_new(
+        multisig_program_id,
+        vec![multisig_state_id, member],
+        vec![],
+        Instruction::CreateMultisig { create_key, threshold: 1, members: vec![*member.value()] },
+    ).unwrap();
+    let ws = WitnessSet::for_message(&msg, &[] as &[&PrivateKey]);
+    submit_tx(&client, PublicTransaction::new(msg, ws)).await;
+
+    let nonce = get_nonce(&client, minter_holding_id).await;
+    let msg = Message::try_new(
+        token_program_id,
+        vec![minter_holding_id, vault_id],
+        vec![nonce],
+        TokenInstruction::Transfer { amount_to_transfer: 500 },
+    ).unwrap();
+    let ws = WitnessSet::for_message(&msg, &[&minter_holding_key]);
+    submit_tx(&client, PublicTransaction::new(msg, ws)).await;
+    assert_eq!(get_balance(&client, vault_id).await, Some(500), "Vault should have 500 tokens");
+
+    // ── Propose a transfer that exceeds its own cap ─────────────────────
+    println!("\n═══ STEP 2: Propose paying 300 with the vault capped at 200 ═══");
+    let recipient_id = account_id_from_key(&PrivateKey::new_os_random());
+    let propose = |index: u64, amount: u128| {
+        let instruction = Instruction::Propose {
+            target_program_id: token_program_id,
+            target_instruction_data: risc0_zkvm::serde::to_vec(
+                &TokenInstruction::Transfer { amount_to_transfer: amount },
+            ).unwrap(),
+            target_account_ids: vec![*vault_id.value(), *recipient_id.value()],
+            pda_seeds: vec![vault_pda_seed_bytes(&create_key)],
+            authorized_indices: vec![0],
+            memo: format!("Pay {} tokens, at most 200 from the vault", amount),
+            content_hash: None,
+            draft: false,
+            depends_on: None,
+            recurrence: None,
+            post_conditions: vec![PostCondition::MaxDataDecrease {
+                account_index: 0,
+                offset: TOKEN_HOLDING_BALANCE_OFFSET,
+                max_decrease: 200,
+            }],
+            expiry: None,
+        };
+        let proposal_id = compute_proposal_pda(&multisig_program_id, &create_key, index);
+        (proposal_id, instruction)
+    };
+    let execute = |proposal_id: AccountId, index: u64, nonce: u128, post_states: Vec<ExpectedAccount>| {
+        let msg = Message::try_new(
+            multisig_program_id,
+            vec![multisig_state_id, member, proposal_id, vault_id, recipient_id],
+            vec![nonce],
+            Instruction::Execute { proposal_index: index, post_states },
+        ).unwrap();
+        let ws = WitnessSet::for_message(&msg, &[&member_key]);
+        PublicTransaction::new(msg, ws)
+    };
+
+    let (proposal_id, instruction) = propose(1, 300);
+    let nonce = get_nonce(&client, member).await;
+    let msg = Message::try_new(
+        multisig_program_id,
+        vec![multisig_state_id, member, proposal_id],
+        vec![nonce],
+        instruction,
+    ).unwrap();
+    let ws = WitnessSet::for_message(&msg, &[&member_key]);
+    submit_tx(&client, PublicTransaction::new(msg, ws)).await;
+
+    // ── Forged post-states: claim the vault only lost 200 ───────────────
+    println!("\n═══ STEP 3: Execute claiming the vault ends at 300 ═══");
+    let forged = vault_with_balance(&client, vault_id, 300).await;
+    let nonce = get_nonce(&client, member).await;
+    submit_tx_expect_rejected(&client, execute(proposal_id, 1, nonce, vec![forged])).await;
+
+    // ── Honest post-states: the check itself fails ──────────────────────
+    println!("\n═══ STEP 4: Execute with the real outcome (vault ends at 200) ═══");
+    let honest = vault_with_balance(&client, vault_id, 200).await;
+    let nonce = get_nonce(&client, member).await;
+    submit_tx_expect_rejected(&client, execute(proposal_id, 1, nonce, vec![honest])).await;
+
+    assert_eq!(get_balance(&client, vault_id).await, Some(500), "Vault should be untouched");
+    assert_eq!(get_proposal(&client, proposal_id).await.status, ProposalStatus::Active);
+    println!("  ✅ Over-cap transfer never landed");
+
+    // ── A transfer within the cap goes through ──────────────────────────
+    println!("\n═══ STEP 5: Propose and execute paying 100 ═══");
+    let (proposal_id, instruction) = propose(2, 100);
+    let nonce = get_nonce(&client, member).await;
+    let msg = Message::try_new(
+        multisig_program_id,
+        vec![multisig_state_id, member, proposal_id],
+        vec![nonce],
+        instruction,
+    ).unwrap();
+    let ws = WitnessSet::for_message(&msg, &[&member_key]);
+    submit_tx(&client, PublicTransaction::new(msg, ws)).await;
+
+    let honest = vault_with_balance(&client, vault_id, 400).await;
+    let nonce = get_nonce(&client, member).await;
+    submit_tx(&client, execute(proposal_id, 2, nonce, vec![honest])).await;
+
+    assert_eq!(get_balance(&client, vault_id).await, Some(400), "Vault should have 400 tokens");
+    assert_eq!(get_balance(&client, recipient_id).await, Some(100), "Recipient should have 100 tokens");
+    assert_eq!(get_proposal(&client, proposal_id).await.status, ProposalStatus::Executed);
+
+    println!("\n🎉 Forged post-states e2e test PASSED!");
+}
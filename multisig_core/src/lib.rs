@@ -11,9 +11,13 @@
 // Inspired by Squads Protocol v4 (Solana).
 
 use borsh::{BorshDeserialize, BorshSerialize};
-use nssa_core::account::AccountId;
+use nssa_core::account::{Account, AccountId};
 use nssa_core::program::{InstructionData, PdaSeed, ProgramId};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// Maximum length (in bytes) of a proposal memo.
+pub const MAX_MEMO_LEN: usize = 256;
 
 // ---------------------------------------------------------------------------
 // Instructions
@@ -26,7 +30,9 @@ use serde::{Deserialize, Serialize};
 /// 2. Other members call `Approve { proposal_index }` — adds their approval
 /// 3. Once M approvals collected, anyone calls `Execute { proposal_index }`
 ///    → multisig emits a ChainedCall to the target program
+///    (or the final approver calls `ApproveAndExecute` to do steps 2 and 3 at once)
 /// 4. Members can also `Reject` proposals
+#[allow(clippy::large_enum_variant)] // decoded once per transaction; boxing would change the wire type
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum Instruction {
     /// Create a new multisig with M-of-N threshold.
@@ -46,12 +52,28 @@ pub enum Instruction {
         target_program_id: ProgramId,
         /// Serialized instruction data for the target program
         target_instruction_data: InstructionData,
-        /// Number of target accounts that will be passed at execute time.
-        target_account_count: u8,
+        /// Exact target account IDs (in order) that must be passed at execute time.
+        /// Members approve these concrete accounts, not just a count.
+        target_account_ids: Vec<[u8; 32]>,
         /// PDA seeds for authorization in the chained call
         pda_seeds: Vec<[u8; 32]>,
         /// Which target account indices (0-based) get `is_authorized = true`
         authorized_indices: Vec<u8>,
+        /// Human-readable title/description (UTF-8, at most `MAX_MEMO_LEN` bytes)
+        memo: String,
+        /// Optional hash of an off-chain document describing the proposal
+        content_hash: Option<[u8; 32]>,
+        /// Create the proposal as a Draft: co-signers can review it on-chain,
+        /// but voting only opens once the proposer sends ActivateProposal
+        draft: bool,
+        /// Index of an earlier proposal that must be Executed before this one can execute
+        depends_on: Option<u64>,
+        /// Make the proposal executable repeatedly on a fixed interval
+        recurrence: Option<Recurrence>,
+        /// Checks on the target accounts after the ChainedCall has run
+        post_conditions: Vec<PostCondition>,
+        /// Deadline after which the proposal can no longer be executed
+        expiry: Option<Expiry>,
     },
 
     /// Approve an existing proposal (any member, one approval per member)
@@ -65,29 +87,300 @@ pub enum Instruction {
     },
 
     /// Execute a fully-approved proposal.
-    /// The transaction must include the target accounts after [multisig_state, executor, proposal].
+    /// The transaction must include the target accounts after [multisig_state, executor, proposal],
+    /// matching the proposal's `target_account_ids` exactly.
     Execute {
         proposal_index: u64,
+        /// For a proposal with post-conditions: the target accounts they refer to
+        /// (see `post_condition_account_indices`) as they will be after its
+        /// ChainedCall. Empty otherwise.
+        post_states: Vec<ExpectedAccount>,
+    },
+
+    /// Approve a proposal and, if this approval reaches threshold, execute it in the
+    /// same transaction. Accounts are laid out as for `Execute`, with the approver
+    /// in place of the executor.
+    ApproveAndExecute {
+        proposal_index: u64,
+        /// As for `Execute`; ignored unless this approval reaches threshold
+        post_states: Vec<ExpectedAccount>,
     },
 
     /// Propose adding a new member to the multisig (requires M approvals to execute).
     ProposeAddMember {
         new_member: [u8; 32],
+        memo: String,
+        content_hash: Option<[u8; 32]>,
     },
 
     /// Propose removing a member from the multisig (requires M approvals to execute).
     /// Will be rejected on execute if removing would make N < M.
     ProposeRemoveMember {
         member: [u8; 32],
+        memo: String,
+        content_hash: Option<[u8; 32]>,
     },
 
     /// Propose changing the approval threshold (requires M approvals to execute).
     /// Must satisfy 1 ≤ new_threshold ≤ N (checked on execute).
     ProposeChangeThreshold {
         new_threshold: u8,
+        memo: String,
+        content_hash: Option<[u8; 32]>,
+    },
+
+    /// Propose allowing (or disallowing) any signer — not just members — to execute
+    /// proposals that have already reached threshold (requires M approvals to execute).
+    ProposeSetPermissionlessExecution {
+        enabled: bool,
+        memo: String,
+        content_hash: Option<[u8; 32]>,
+    },
+
+    /// Upgrade a multisig's state account — and optionally some of its proposal
+    /// accounts — to the current schema version in place (any member can call).
+    /// Accounts: [multisig_state, member (signer), proposal_pda...]
+    MigrateState,
+
+    /// Propose moving this multisig to a new program ID (requires M approvals to execute).
+    /// On execute, the multisig is recreated under `new_program_id` via `ImportMultisig`
+    /// and each handover call runs with the old vault's PDA authority.
+    ProposeMigrateToProgram {
+        new_program_id: ProgramId,
+        handover_calls: Vec<HandoverCall>,
+        memo: String,
+        content_hash: Option<[u8; 32]>,
+    },
+
+    /// Recreate a multisig migrated from another program ID. Only reachable via
+    /// ChainedCall from an executed `MigrateToProgram` proposal: the old state
+    /// must be authorized by the old program's PDA seed and name this program
+    /// in `migrated_to`.
+    /// Accounts: [multisig_state (uninitialized, under this program),
+    ///            old multisig_state (authorized, under the old program)]
+    ImportMultisig,
+
+    /// Propose setting (or clearing) the reject threshold: once this many
+    /// current members reject a proposal, it is Rejected regardless of
+    /// whether approval is still reachable.
+    ///
+    /// Required accounts: same as ProposeAddMember
+    ProposeSetRejectThreshold {
+        reject_threshold: Option<u8>,
+        memo: String,
+        content_hash: Option<[u8; 32]>,
+    },
+
+    /// Open a Draft proposal for voting (proposer only)
+    ///
+    /// Required accounts:
+    /// - multisig_state (PDA)
+    /// - proposer (signer)
+    /// - proposal (PDA)
+    ActivateProposal {
+        proposal_index: u64,
+    },
+
+    /// Check a proposal's post-conditions against the target accounts as they
+    /// are after its ChainedCall. Only meant to be reached via the follow-up
+    /// ChainedCall that `Execute` emits; it changes no account.
+    /// Accounts: the target accounts the conditions refer to, in
+    /// `post_condition_account_indices` order
+    AssertPostConditions {
+        conditions: Vec<PostCondition>,
+        /// `PostCondition::snapshot` of each condition, taken before the ChainedCall
+        snapshots: Vec<u128>,
+    },
+
+    /// Mark an Active or Draft proposal Expired once its deadline has passed
+    /// (anyone can call; no signer needed)
+    ///
+    /// Required accounts:
+    /// - multisig_state (PDA)
+    /// - proposal (PDA)
+    /// - the proposal's clock account
+    ExpireProposal {
+        proposal_index: u64,
     },
 }
 
+/// A ChainedCall run with the vault's PDA authority during a program migration,
+/// e.g. a token transfer from the old vault to the new one.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct HandoverCall {
+    /// Program to call (e.g. the token program)
+    pub program_id: ProgramId,
+    /// Serialized instruction data for that program
+    pub instruction_data: InstructionData,
+    /// Exact accounts passed to the call, in order
+    pub account_ids: Vec<[u8; 32]>,
+    /// Which of `account_ids` (0-based) get `is_authorized = true`
+    pub authorized_indices: Vec<u8>,
+}
+
+/// Schedule for a proposal that, once approved, executes once per `interval`
+/// up to `max_runs` times (e.g. monthly payroll).
+///
+/// Time comes from `clock_account_id`, fixed at propose time so members approve
+/// which clock they trust: its data must start with the current Unix time in
+/// seconds as a little-endian u64.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct Recurrence {
+    /// Account whose data holds the current time (see `clock_timestamp`)
+    pub clock_account_id: [u8; 32],
+    /// Minimum seconds between two runs
+    pub interval: u64,
+    /// Total number of runs; the proposal is Executed after the last one
+    pub max_runs: u32,
+}
+
+/// Deadline of a proposal: from `expires_at` on, it can no longer be executed
+/// and anyone can mark it Expired. Time comes from `clock_account_id`, as for
+/// `Recurrence`; a proposal with both must use the same clock.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct Expiry {
+    /// Account whose data holds the current time (see `clock_timestamp`)
+    pub clock_account_id: [u8; 32],
+    /// Unix time in seconds at which the proposal expires
+    pub expires_at: u64,
+}
+
+/// Read the timestamp from a clock account's data (first 8 bytes, little-endian u64).
+pub fn clock_timestamp(data: &[u8]) -> Option<u64> {
+    let bytes: [u8; 8] = data.get(..8)?.try_into().ok()?;
+    Some(u64::from_le_bytes(bytes))
+}
+
+/// A check on one of a proposal's target accounts after its ChainedCall has
+/// run, so an approved call into a misbehaving program cannot take more than
+/// members agreed to. `account_index` is 0-based into `target_account_ids`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub enum PostCondition {
+    /// The account's native balance may drop by at most `max_decrease`
+    MaxBalanceDecrease { account_index: u8, max_decrease: u128 },
+    /// A little-endian u128 at byte `offset` of the account's data (e.g. a token
+    /// holding's balance) may drop by at most `max_decrease`; missing bytes read as 0
+    MaxDataDecrease { account_index: u8, offset: u32, max_decrease: u128 },
+    /// The account must exist (differ from the default account) after the call
+    AccountExists { account_index: u8 },
+}
+
+impl PostCondition {
+    pub fn account_index(&self) -> u8 {
+        match self {
+            Self::MaxBalanceDecrease { account_index, .. }
+            | Self::MaxDataDecrease { account_index, .. }
+            | Self::AccountExists { account_index } => *account_index,
+        }
+    }
+
+    /// The value this condition compares against, read before the ChainedCall.
+    pub fn snapshot(&self, account: &Account) -> u128 {
+        match self {
+            Self::MaxBalanceDecrease { .. } => account.balance,
+            Self::MaxDataDecrease { offset, .. } => data_u128(account, *offset),
+            Self::AccountExists { .. } => 0,
+        }
+    }
+
+    /// Check the account after the ChainedCall against the `snapshot` taken before it.
+    pub fn check(&self, snapshot: u128, account: &Account) -> Result<(), PostConditionError> {
+        let account_index = self.account_index();
+        let (after, max_decrease) = match self {
+            Self::MaxBalanceDecrease { max_decrease, .. } => (account.balance, *max_decrease),
+            Self::MaxDataDecrease { offset, max_decrease, .. } => (data_u128(account, *offset), *max_decrease),
+            Self::AccountExists { .. } => {
+                if *account == Account::default() {
+                    return Err(PostConditionError::AccountMissing { account_index });
+                }
+                return Ok(());
+            }
+        };
+        let decrease = snapshot.saturating_sub(after);
+        if decrease > max_decrease {
+            return Err(PostConditionError::DecreasedTooMuch { account_index, decrease, max_decrease });
+        }
+        Ok(())
+    }
+}
+
+/// Target account indices that `conditions` refer to, ascending and without
+/// duplicates. `AssertPostConditions` receives these accounts, in this order.
+pub fn post_condition_account_indices(conditions: &[PostCondition]) -> Vec<u8> {
+    let mut indices: Vec<u8> = conditions.iter().map(PostCondition::account_index).collect();
+    indices.sort_unstable();
+    indices.dedup();
+    indices
+}
+
+/// A target account as the executor expects it after the proposal's ChainedCall.
+///
+/// The multisig cannot read accounts after a call it emits: a ChainedCall's
+/// accounts are fixed by its caller. So `Execute` passes these as the accounts
+/// of the follow-up `AssertPostConditions` call, and the runtime only runs a
+/// call whose accounts match the actual ones at that point. A wrong
+/// expectation aborts the transaction; a right one lets the check see the
+/// real outcome.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExpectedAccount {
+    pub program_owner: ProgramId,
+    pub balance: u128,
+    pub data: Vec<u8>,
+    pub nonce: u128,
+}
+
+impl ExpectedAccount {
+    pub fn to_account(&self) -> Account {
+        Account {
+            program_owner: self.program_owner,
+            balance: self.balance,
+            data: self.data.clone().try_into().expect("Expected account data is too large"),
+            nonce: self.nonce,
+        }
+    }
+}
+
+impl From<&Account> for ExpectedAccount {
+    fn from(account: &Account) -> Self {
+        Self {
+            program_owner: account.program_owner,
+            balance: account.balance,
+            data: account.data.clone().into(),
+            nonce: account.nonce,
+        }
+    }
+}
+
+fn data_u128(account: &Account, offset: u32) -> u128 {
+    let data: Vec<u8> = account.data.clone().into();
+    data.get(offset as usize..)
+        .and_then(|rest| rest.get(..16))
+        .map(|bytes| u128::from_le_bytes(bytes.try_into().unwrap()))
+        .unwrap_or(0)
+}
+
+/// Why a post-condition failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PostConditionError {
+    DecreasedTooMuch { account_index: u8, decrease: u128, max_decrease: u128 },
+    AccountMissing { account_index: u8 },
+}
+
+impl core::fmt::Display for PostConditionError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::DecreasedTooMuch { account_index, decrease, max_decrease } => write!(
+                f,
+                "Target account {} decreased by {}, more than the approved {}",
+                account_index, decrease, max_decrease
+            ),
+            Self::AccountMissing { account_index } => {
+                write!(f, "Target account {} does not exist after execution", account_index)
+            }
+        }
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Proposal state (stored in its own PDA account)
 // ---------------------------------------------------------------------------
@@ -103,6 +396,15 @@ pub enum ConfigAction {
     RemoveMember { member: [u8; 32] },
     /// Change the approval threshold
     ChangeThreshold { new_threshold: u8 },
+    /// Allow any signer (e.g. a relayer or keeper bot) to execute approved proposals
+    SetPermissionlessExecution { enabled: bool },
+    /// Recreate the multisig under a new program ID and hand over vault holdings
+    MigrateToProgram {
+        new_program_id: ProgramId,
+        handover_calls: Vec<HandoverCall>,
+    },
+    /// Set or clear the number of rejections that kills a proposal
+    SetRejectThreshold { reject_threshold: Option<u8> },
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
@@ -115,6 +417,10 @@ pub enum ProposalStatus {
     Rejected,
     /// Proposal was cancelled
     Cancelled,
+    /// Proposal is visible on-chain for review but not yet open for voting
+    Draft,
+    /// Proposal passed its deadline without being executed
+    Expired,
 }
 
 /// A proposal stored in its own PDA account.
@@ -133,8 +439,8 @@ pub struct Proposal {
     pub target_program_id: ProgramId,
     /// Serialized instruction data for target program
     pub target_instruction_data: InstructionData,
-    /// Expected number of target accounts at execute time
-    pub target_account_count: u8,
+    /// Target account IDs the executor must pass, in order (checked at execute time)
+    pub target_account_ids: Vec<[u8; 32]>,
     /// PDA seeds for the chained call (multisig proves ownership)
     pub pda_seeds: Vec<[u8; 32]>,
     /// Which target account indices (0-based) get `is_authorized = true`
@@ -149,6 +455,32 @@ pub struct Proposal {
     pub status: ProposalStatus,
     /// Optional config change action (if set, execute modifies MultisigState instead of ChainedCall)
     pub config_action: Option<ConfigAction>,
+
+    // -- Context for signers --
+    /// Human-readable title/description shown to members before they vote
+    pub memo: String,
+    /// Optional hash of an off-chain document (e.g. payment batch, spec) backing the proposal
+    pub content_hash: Option<[u8; 32]>,
+
+    // -- Ordering --
+    /// Earlier proposal that must be Executed first; its PDA is passed to Execute
+    pub depends_on: Option<u64>,
+
+    // -- Recurring execution --
+    /// If set, Execute runs the proposal once per interval instead of once
+    pub recurrence: Option<Recurrence>,
+    /// Runs executed so far (recurring proposals only)
+    pub runs_executed: u32,
+    /// Clock time of the latest run (recurring proposals only)
+    pub last_executed_at: Option<u64>,
+
+    // -- Outcome checks --
+    /// Checked on the target accounts by a follow-up ChainedCall after each run
+    pub post_conditions: Vec<PostCondition>,
+
+    // -- Deadline --
+    /// If set, the proposal can no longer execute from `expires_at` on
+    pub expiry: Option<Expiry>,
 }
 
 impl Proposal {
@@ -158,9 +490,11 @@ impl Proposal {
         multisig_create_key: [u8; 32],
         target_program_id: ProgramId,
         target_instruction_data: InstructionData,
-        target_account_count: u8,
+        target_account_ids: Vec<[u8; 32]>,
         pda_seeds: Vec<[u8; 32]>,
         authorized_indices: Vec<u8>,
+        memo: String,
+        content_hash: Option<[u8; 32]>,
     ) -> Self {
         Self {
             index,
@@ -168,13 +502,21 @@ impl Proposal {
             multisig_create_key,
             target_program_id,
             target_instruction_data,
-            target_account_count,
+            target_account_ids,
             pda_seeds,
             authorized_indices,
             approved: vec![proposer],
             rejected: vec![],
             status: ProposalStatus::Active,
             config_action: None,
+            memo,
+            content_hash,
+            depends_on: None,
+            recurrence: None,
+            runs_executed: 0,
+            last_executed_at: None,
+            post_conditions: vec![],
+            expiry: None,
         }
     }
 
@@ -184,6 +526,8 @@ impl Proposal {
         proposer: [u8; 32],
         multisig_create_key: [u8; 32],
         action: ConfigAction,
+        memo: String,
+        content_hash: Option<[u8; 32]>,
     ) -> Self {
         Self {
             index,
@@ -191,13 +535,21 @@ impl Proposal {
             multisig_create_key,
             target_program_id: [0u32; 8],
             target_instruction_data: vec![],
-            target_account_count: 0,
+            target_account_ids: vec![],
             pda_seeds: vec![],
             authorized_indices: vec![],
             approved: vec![proposer],
             rejected: vec![],
             status: ProposalStatus::Active,
             config_action: Some(action),
+            memo,
+            content_hash,
+            depends_on: None,
+            recurrence: None,
+            runs_executed: 0,
+            last_executed_at: None,
+            post_conditions: vec![],
+            expiry: None,
         }
     }
 
@@ -221,15 +573,265 @@ impl Proposal {
         true
     }
 
-    /// Check if the proposal has enough approvals
-    pub fn has_threshold(&self, threshold: u8) -> bool {
-        self.approved.len() >= threshold as usize
+    /// Target accounts the executor must pass after [multisig_state, executor, proposal], in order.
+    pub fn expected_target_account_ids(&self) -> Vec<[u8; 32]> {
+        match &self.config_action {
+            None => self.target_account_ids.clone(),
+            Some(ConfigAction::MigrateToProgram { new_program_id, handover_calls }) => {
+                let new_state_pda = compute_multisig_state_pda(new_program_id, &self.multisig_create_key);
+                let mut ids = vec![*new_state_pda.value()];
+                for call in handover_calls {
+                    ids.extend(call.account_ids.iter().copied());
+                }
+                ids
+            }
+            Some(_) => vec![],
+        }
+    }
+
+    /// Approvals cast by current members. Votes of members removed since do not count.
+    pub fn approval_count(&self, state: &MultisigState) -> usize {
+        self.approved.iter().filter(|m| state.is_member(m)).count()
+    }
+
+    /// Rejections cast by current members.
+    pub fn rejection_count(&self, state: &MultisigState) -> usize {
+        self.rejected.iter().filter(|m| state.is_member(m)).count()
+    }
+
+    /// Accounts the executor must pass after [multisig_state, executor, proposal]:
+    /// the dependency's proposal PDA and the clock account (each if any), then the
+    /// target accounts.
+    pub fn execute_account_ids(&self, program_id: &ProgramId) -> Vec<[u8; 32]> {
+        let mut ids: Vec<[u8; 32]> = self
+            .depends_on
+            .map(|index| *compute_proposal_pda(program_id, &self.multisig_create_key, index).value())
+            .into_iter()
+            .collect();
+        ids.extend(self.clock_account_id());
+        ids.extend(self.expected_target_account_ids());
+        ids
+    }
+
+    /// Clock account of a recurring or expiring proposal, `None` otherwise.
+    pub fn clock_account_id(&self) -> Option<[u8; 32]> {
+        self.recurrence
+            .as_ref()
+            .map(|r| r.clock_account_id)
+            .or_else(|| self.expiry.as_ref().map(|e| e.clock_account_id))
     }
 
-    /// Check if the proposal can never reach threshold
-    pub fn is_dead(&self, threshold: u8, member_count: u8) -> bool {
-        let remaining = member_count as usize - self.rejected.len();
-        remaining < threshold as usize
+    /// Runs left for a recurring proposal, `None` otherwise.
+    pub fn remaining_runs(&self) -> Option<u32> {
+        self.recurrence.as_ref().map(|r| r.max_runs.saturating_sub(self.runs_executed))
+    }
+
+    /// Earliest clock time of the next run, `None` if not recurring or never run.
+    pub fn next_run_at(&self) -> Option<u64> {
+        let interval = self.recurrence.as_ref()?.interval;
+        self.last_executed_at.map(|last| last.saturating_add(interval))
+    }
+
+    /// Check if the proposal has enough approvals from current members
+    pub fn has_threshold(&self, state: &MultisigState) -> bool {
+        self.approval_count(state) >= state.threshold as usize
+    }
+
+    /// Encode for storage in the proposal account (discriminator + version + borsh).
+    pub fn to_bytes(&self) -> Vec<u8> {
+        encode_account(&PROPOSAL_DISCRIMINATOR, PROPOSAL_VERSION, self)
+    }
+
+    /// Decode a proposal account. Fails unless the data carries the proposal discriminator.
+    pub fn from_bytes(data: &[u8]) -> Result<Self, borsh::io::Error> {
+        let (version, body) = split_header(&PROPOSAL_DISCRIMINATOR, data)?;
+        match version {
+            PROPOSAL_VERSION => borsh::from_slice(body),
+            v => Err(unsupported_version(v)),
+        }
+    }
+
+    /// Decode a proposal account, also accepting the original plain-borsh
+    /// layout when no discriminator is present. Only `MigrateState` should need this.
+    pub fn from_legacy_bytes(data: &[u8]) -> Result<Self, borsh::io::Error> {
+        if has_discriminator(data) {
+            return Self::from_bytes(data);
+        }
+        borsh::from_slice::<ProposalV0>(data).map(Into::into)
+    }
+
+    /// Check if the proposal can never reach threshold with the current members
+    pub fn is_dead(&self, state: &MultisigState) -> bool {
+        let remaining = state.members.len().saturating_sub(self.rejection_count(state));
+        remaining < state.threshold as usize
+    }
+
+    /// Check if enough current members rejected to meet the reject threshold, if one is set
+    pub fn is_vetoed(&self, state: &MultisigState) -> bool {
+        state
+            .reject_threshold
+            .is_some_and(|cutoff| self.rejection_count(state) >= cutoff as usize)
+    }
+
+    /// Apply a lifecycle event, returning the new status. On error the proposal
+    /// is left unchanged. This is the only place that changes `status`.
+    pub fn apply(&mut self, state: &MultisigState, event: ProposalEvent) -> Result<ProposalStatus, LifecycleError> {
+        match (self.status, event) {
+            (ProposalStatus::Draft, ProposalEvent::Activate(member) | ProposalEvent::Cancel(member)) => {
+                if member != self.proposer {
+                    return Err(LifecycleError::NotProposer);
+                }
+                if !state.is_member(&member) {
+                    return Err(LifecycleError::NotMember);
+                }
+            }
+            (ProposalStatus::Draft, ProposalEvent::Expire { .. }) => {}
+            (_, ProposalEvent::Activate(_)) => return Err(LifecycleError::NotDraft),
+            (ProposalStatus::Active, _) => {}
+            _ => return Err(LifecycleError::NotActive),
+        }
+        match event {
+            ProposalEvent::Activate(_) => {
+                self.status = ProposalStatus::Active;
+            }
+            ProposalEvent::Approve(member) => {
+                if !state.is_member(&member) {
+                    return Err(LifecycleError::NotMember);
+                }
+                if !self.approve(member) {
+                    return Err(LifecycleError::AlreadyApproved);
+                }
+            }
+            ProposalEvent::Reject(member) => {
+                if !state.is_member(&member) {
+                    return Err(LifecycleError::NotMember);
+                }
+                if !self.reject(member) {
+                    return Err(LifecycleError::AlreadyRejected);
+                }
+                if self.is_dead(state) || self.is_vetoed(state) {
+                    self.status = ProposalStatus::Rejected;
+                }
+            }
+            ProposalEvent::Execute { executor, now } => {
+                if !state.can_execute(&executor) {
+                    return Err(LifecycleError::NotMember);
+                }
+                if !self.has_threshold(state) {
+                    return Err(LifecycleError::BelowThreshold {
+                        need: state.threshold,
+                        have: self.approval_count(state),
+                    });
+                }
+                if let Some(expiry) = &self.expiry {
+                    let now = now.ok_or(LifecycleError::MissingClock)?;
+                    if now >= expiry.expires_at {
+                        return Err(LifecycleError::Expired { expires_at: expiry.expires_at });
+                    }
+                }
+                match &self.recurrence {
+                    None => self.status = ProposalStatus::Executed,
+                    Some(recurrence) => {
+                        let now = now.ok_or(LifecycleError::MissingClock)?;
+                        if let Some(next_at) = self.next_run_at()
+                            && now < next_at
+                        {
+                            return Err(LifecycleError::NotDue { next_at });
+                        }
+                        self.runs_executed += 1;
+                        self.last_executed_at = Some(now);
+                        if self.runs_executed >= recurrence.max_runs {
+                            self.status = ProposalStatus::Executed;
+                        }
+                    }
+                }
+            }
+            ProposalEvent::Cancel(member) => {
+                if member != self.proposer {
+                    return Err(LifecycleError::NotProposer);
+                }
+                self.status = ProposalStatus::Cancelled;
+            }
+            ProposalEvent::Expire { now } => {
+                let expiry = self.expiry.as_ref().ok_or(LifecycleError::NoExpiry)?;
+                if now < expiry.expires_at {
+                    return Err(LifecycleError::NotExpired { expires_at: expiry.expires_at });
+                }
+                self.status = ProposalStatus::Expired;
+            }
+        }
+        Ok(self.status)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Proposal lifecycle
+// ---------------------------------------------------------------------------
+//
+//   Draft  ──Activate by proposer──────────▶ Active
+//   Draft  ──Cancel by proposer────────────▶ Cancelled
+//   Draft  ──Expire, deadline passed───────▶ Expired
+//   Active ──Approve / Reject──────────────▶ Active
+//   Active ──Reject, unreachable or vetoed─▶ Rejected
+//   Active ──Execute, threshold met────────▶ Executed
+//   Active ──Execute, recurring, runs left─▶ Active (once per interval)
+//   Active ──Cancel by proposer────────────▶ Cancelled
+//   Active ──Expire, deadline passed───────▶ Expired
+//
+// Executed, Rejected, Cancelled and Expired are final. Only current members'
+// votes are counted, so membership changes after a vote can neither push a
+// proposal over the threshold nor make the dead check underflow. A proposal
+// with an expiry cannot execute from its deadline on, even before it is marked
+// Expired. Cancel is not exposed as an instruction yet.
+
+/// An event that moves a proposal through its lifecycle. Each carries the acting account.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProposalEvent {
+    Approve([u8; 32]),
+    Reject([u8; 32]),
+    /// `now` is the clock time, required for recurring proposals
+    Execute { executor: [u8; 32], now: Option<u64> },
+    Cancel([u8; 32]),
+    Activate([u8; 32]),
+    /// `now` is the clock time; anyone may send it
+    Expire { now: u64 },
+}
+
+/// Why a lifecycle event was refused.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LifecycleError {
+    NotActive,
+    NotDraft,
+    NotMember,
+    NotProposer,
+    AlreadyApproved,
+    AlreadyRejected,
+    BelowThreshold { need: u8, have: usize },
+    MissingClock,
+    NotDue { next_at: u64 },
+    NoExpiry,
+    NotExpired { expires_at: u64 },
+    Expired { expires_at: u64 },
+}
+
+impl core::fmt::Display for LifecycleError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::NotActive => write!(f, "Proposal is not active"),
+            Self::NotDraft => write!(f, "Proposal is not a draft"),
+            Self::NotMember => write!(f, "Account is not a multisig member"),
+            Self::NotProposer => write!(f, "Only the proposer can do this"),
+            Self::AlreadyApproved => write!(f, "Member has already approved this proposal"),
+            Self::AlreadyRejected => write!(f, "Member has already rejected this proposal"),
+            Self::BelowThreshold { need, have } => {
+                write!(f, "Proposal does not have enough approvals: need {}, have {}", need, have)
+            }
+            Self::MissingClock => write!(f, "Recurring or expiring proposal requires the clock time"),
+            Self::NotDue { next_at } => write!(f, "Next run is not due until {}", next_at),
+            Self::NoExpiry => write!(f, "Proposal has no expiry"),
+            Self::NotExpired { expires_at } => write!(f, "Proposal does not expire until {}", expires_at),
+            Self::Expired { expires_at } => write!(f, "Proposal expired at {}", expires_at),
+        }
     }
 }
 
@@ -245,10 +847,18 @@ pub struct MultisigState {
     pub threshold: u8,
     /// Number of members (N)
     pub member_count: u8,
-    /// List of member account IDs
+    /// List of member account IDs (a member may be another multisig's vault PDA)
     pub members: Vec<[u8; 32]>,
     /// Transaction/proposal counter (incremented on each Propose)
     pub transaction_index: u64,
+    /// If true, any signer may execute a proposal that has reached threshold
+    pub permissionless_execution: bool,
+    /// If set, this many rejections by current members mark a proposal Rejected
+    /// even while approval is still reachable (a minority veto)
+    pub reject_threshold: Option<u8>,
+    /// Set once a `MigrateToProgram` proposal has executed; no further
+    /// proposals can be created here
+    pub migrated_to: Option<ProgramId>,
 }
 
 impl MultisigState {
@@ -260,6 +870,9 @@ impl MultisigState {
             member_count,
             members,
             transaction_index: 0,
+            permissionless_execution: false,
+            reject_threshold: None,
+            migrated_to: None,
         }
     }
 
@@ -267,6 +880,46 @@ impl MultisigState {
         self.members.contains(id)
     }
 
+    /// Whether `id` may execute approved proposals of this multisig
+    pub fn can_execute(&self, id: &[u8; 32]) -> bool {
+        self.permissionless_execution || self.is_member(id)
+    }
+
+    /// Encode for storage in the state account (discriminator + version + borsh).
+    pub fn to_bytes(&self) -> Vec<u8> {
+        encode_account(&MULTISIG_STATE_DISCRIMINATOR, MULTISIG_STATE_VERSION, self)
+    }
+
+    /// Decode a state account. Fails unless the data carries the state discriminator.
+    pub fn from_bytes(data: &[u8]) -> Result<Self, borsh::io::Error> {
+        let (version, body) = split_header(&MULTISIG_STATE_DISCRIMINATOR, data)?;
+        match version {
+            MULTISIG_STATE_VERSION => borsh::from_slice(body),
+            v => Err(unsupported_version(v)),
+        }
+    }
+
+    /// Decode a state account, also accepting the original plain-borsh
+    /// layout when no discriminator is present. Only `MigrateState` should need this.
+    pub fn from_legacy_bytes(data: &[u8]) -> Result<Self, borsh::io::Error> {
+        if has_discriminator(data) {
+            return Self::from_bytes(data);
+        }
+        let state: Self = borsh::from_slice::<MultisigStateV0>(data)?.into();
+        // Plain borsh carries no type tag, so reject anything that does not
+        // look like a multisig (e.g. a legacy proposal that happens to parse).
+        let consistent = state.members.len() == state.member_count as usize
+            && state.threshold >= 1
+            && state.threshold <= state.member_count;
+        if !consistent {
+            return Err(borsh::io::Error::new(
+                borsh::io::ErrorKind::InvalidData,
+                "Legacy state is inconsistent",
+            ));
+        }
+        Ok(state)
+    }
+
     /// Increment and return the next proposal index
     pub fn next_proposal_index(&mut self) -> u64 {
         self.transaction_index += 1;
@@ -274,6 +927,138 @@ impl MultisigState {
     }
 }
 
+// ---------------------------------------------------------------------------
+// Account encoding
+// ---------------------------------------------------------------------------
+//
+// Accounts written by the program are stored as
+//
+//     discriminator (8 bytes) ‖ schema version (u8) ‖ borsh(struct)
+//
+// The discriminator identifies the account type, so bytes of one type are
+// never decoded as another (e.g. a Proposal passed where MultisigState is
+// expected). `from_bytes` rejects data without the right discriminator.
+//
+// The only older layout is the original one, plain borsh with no prefix
+// (`*V0`). It is only readable via `from_legacy_bytes`, and only when the data
+// does not start with a discriminator; `Instruction::MigrateState` uses it to
+// rewrite such accounts in place.
+//
+// When a field is added, bump the version and keep the previous layout as a
+// `*V<n>` struct with a `From` conversion matched on its version in `from_bytes`.
+
+/// Type tag at the start of every `MultisigState` account.
+pub const MULTISIG_STATE_DISCRIMINATOR: [u8; 8] = *b"msigstat";
+/// Type tag at the start of every `Proposal` account.
+pub const PROPOSAL_DISCRIMINATOR: [u8; 8] = *b"msigprop";
+
+/// Current schema version of `MultisigState` accounts.
+pub const MULTISIG_STATE_VERSION: u8 = 1;
+/// Current schema version of `Proposal` accounts.
+pub const PROPOSAL_VERSION: u8 = 1;
+
+fn encode_account<T: BorshSerialize>(discriminator: &[u8; 8], version: u8, value: &T) -> Vec<u8> {
+    let mut bytes = discriminator.to_vec();
+    bytes.push(version);
+    value.serialize(&mut bytes).unwrap();
+    bytes
+}
+
+/// Check the discriminator and split off the schema version.
+fn split_header<'a>(discriminator: &[u8; 8], data: &'a [u8]) -> Result<(u8, &'a [u8]), borsh::io::Error> {
+    let rest = data.strip_prefix(&discriminator[..]).ok_or_else(|| {
+        borsh::io::Error::new(borsh::io::ErrorKind::InvalidData, "Account discriminator mismatch")
+    })?;
+    rest.split_first()
+        .map(|(version, body)| (*version, body))
+        .ok_or_else(|| borsh::io::Error::new(borsh::io::ErrorKind::InvalidData, "Missing schema version"))
+}
+
+fn unsupported_version(version: u8) -> borsh::io::Error {
+    borsh::io::Error::new(
+        borsh::io::ErrorKind::InvalidData,
+        format!("Unsupported schema version {}", version),
+    )
+}
+
+/// Whether `data` starts with the discriminator of any account type, in which
+/// case it must never be decoded as a legacy layout.
+fn has_discriminator(data: &[u8]) -> bool {
+    data.starts_with(&MULTISIG_STATE_DISCRIMINATOR) || data.starts_with(&PROPOSAL_DISCRIMINATOR)
+}
+
+/// Original (unversioned) `MultisigState` layout.
+#[derive(BorshDeserialize)]
+struct MultisigStateV0 {
+    create_key: [u8; 32],
+    threshold: u8,
+    member_count: u8,
+    members: Vec<[u8; 32]>,
+    transaction_index: u64,
+}
+
+impl From<MultisigStateV0> for MultisigState {
+    fn from(v0: MultisigStateV0) -> Self {
+        Self {
+            create_key: v0.create_key,
+            threshold: v0.threshold,
+            member_count: v0.member_count,
+            members: v0.members,
+            transaction_index: v0.transaction_index,
+            permissionless_execution: false,
+            reject_threshold: None,
+            migrated_to: None,
+        }
+    }
+}
+
+/// Original (unversioned) `Proposal` layout.
+#[derive(BorshDeserialize)]
+struct ProposalV0 {
+    index: u64,
+    proposer: [u8; 32],
+    multisig_create_key: [u8; 32],
+    target_program_id: ProgramId,
+    target_instruction_data: InstructionData,
+    /// V0 only recorded how many target accounts to expect, not which ones
+    _target_account_count: u8,
+    pda_seeds: Vec<[u8; 32]>,
+    authorized_indices: Vec<u8>,
+    approved: Vec<[u8; 32]>,
+    rejected: Vec<[u8; 32]>,
+    status: ProposalStatus,
+    config_action: Option<ConfigAction>,
+}
+
+impl From<ProposalV0> for Proposal {
+    /// V0 transfer proposals carry no target account binding, so they decode
+    /// with an empty `target_account_ids` and must be re-proposed to execute.
+    fn from(v0: ProposalV0) -> Self {
+        Self {
+            index: v0.index,
+            proposer: v0.proposer,
+            multisig_create_key: v0.multisig_create_key,
+            target_program_id: v0.target_program_id,
+            target_instruction_data: v0.target_instruction_data,
+            target_account_ids: vec![],
+            pda_seeds: v0.pda_seeds,
+            authorized_indices: v0.authorized_indices,
+            approved: v0.approved,
+            rejected: v0.rejected,
+            status: v0.status,
+            config_action: v0.config_action,
+            memo: String::new(),
+            content_hash: None,
+            depends_on: None,
+            recurrence: None,
+            runs_executed: 0,
+            last_executed_at: None,
+            post_conditions: vec![],
+            expiry: None,
+        }
+    }
+}
+
 // ---------------------------------------------------------------------------
 // PDA derivation helpers
 // ---------------------------------------------------------------------------
@@ -352,3 +1137,351 @@ pub fn vault_pda_seed_bytes(create_key: &[u8; 32]) -> [u8; 32] {
     }
     seed
 }
+
+// ---------------------------------------------------------------------------
+// Deterministic create keys
+// ---------------------------------------------------------------------------
+//
+// Any 32 bytes work as a create_key, but a random one is lost if nobody writes
+// it down. Deriving it from (creator account, label, nonce) lets the creator
+// find the multisig again by re-deriving keys for increasing nonces and
+// checking which state PDAs exist. Nothing on-chain depends on this scheme.
+
+/// Domain separator for `derive_create_key`.
+const CREATE_KEY_DOMAIN: &[u8] = b"lez-multisig/create_key/v1";
+
+/// Derive a create_key as
+/// `SHA256(domain ‖ creator ‖ len(label) as u32 LE ‖ label ‖ nonce as u64 LE)`.
+pub fn derive_create_key(creator: &[u8; 32], label: &str, nonce: u64) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(CREATE_KEY_DOMAIN);
+    hasher.update(creator);
+    hasher.update((label.len() as u32).to_le_bytes());
+    hasher.update(label.as_bytes());
+    hasher.update(nonce.to_le_bytes());
+    hasher.finalize().into()
+}
+
+// ---------------------------------------------------------------------------
+// Nested multisigs
+// ---------------------------------------------------------------------------
+//
+// A multisig ("child") can be a member of another multisig ("parent") by adding
+// the child's vault PDA as a member. The child votes by executing one of its own
+// proposals whose ChainedCall targets the multisig program's `Approve` (or
+// `Reject`) for the parent proposal. The vault PDA seed in `pda_seeds` makes the
+// vault `is_authorized` in the chained call, exactly like a member signature.
+
+/// Index of the voting child vault within `nested_vote_target_account_ids`.
+/// Must be listed in the child proposal's `authorized_indices`.
+pub const NESTED_VOTE_VOTER_INDEX: u8 = 1;
+
+/// Target accounts for a child proposal that votes on a parent proposal:
+/// `[parent_state, child_vault, parent_proposal]` — the parent's Approve/Reject layout.
+pub fn nested_vote_target_account_ids(
+    program_id: &ProgramId,
+    parent_create_key: &[u8; 32],
+    parent_proposal_index: u64,
+    child_create_key: &[u8; 32],
+) -> Vec<[u8; 32]> {
+    vec![
+        *compute_multisig_state_pda(program_id, parent_create_key).value(),
+        *compute_vault_pda(program_id, child_create_key).value(),
+        *compute_proposal_pda(program_id, parent_create_key, parent_proposal_index).value(),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    /// A step in a generated history: a lifecycle event on the proposal, or a
+    /// config change executed through some other proposal in the meantime.
+    #[derive(Debug, Clone, Copy)]
+    enum Step {
+        Event(ProposalEvent),
+        AddMember([u8; 32]),
+        RemoveMember([u8; 32]),
+        ChangeThreshold(u8),
+        SetRejectThreshold(Option<u8>),
+    }
+
+    const ACTORS: [[u8; 32]; 4] = [[1u8; 32], [2u8; 32], [3u8; 32], [4u8; 32]];
+    const CLOCK: [u8; 32] = [9u8; 32];
+    /// Clock readings, around the intervals and deadlines generated below
+    const TIMES: [u64; 6] = [0, 50, 99, 100, 200, 300];
+    const MAX_STEPS: usize = 12;
+    const CASES: u32 = 2_000;
+
+    fn actor() -> impl Strategy<Value = [u8; 32]> {
+        prop::sample::select(ACTORS.to_vec())
+    }
+
+    fn time() -> impl Strategy<Value = u64> {
+        prop::sample::select(TIMES.to_vec())
+    }
+
+    fn any_step() -> impl Strategy<Value = Step> {
+        prop_oneof![
+            actor().prop_map(|a| Step::Event(ProposalEvent::Approve(a))),
+            actor().prop_map(|a| Step::Event(ProposalEvent::Reject(a))),
+            (actor(), prop::option::of(time()))
+                .prop_map(|(executor, now)| Step::Event(ProposalEvent::Execute { executor, now })),
+            actor().prop_map(|a| Step::Event(ProposalEvent::Cancel(a))),
+            actor().prop_map(|a| Step::Event(ProposalEvent::Activate(a))),
+            time().prop_map(|now| Step::Event(ProposalEvent::Expire { now })),
+            actor().prop_map(Step::AddMember),
+            actor().prop_map(Step::RemoveMember),
+            (1u8..=3).prop_map(Step::ChangeThreshold),
+            prop::option::of(1u8..=2).prop_map(Step::SetRejectThreshold),
+        ]
+    }
+
+    /// A fresh proposal of a 3-member multisig, active or draft, optionally
+    /// recurring and/or expiring.
+    fn any_proposal() -> impl Strategy<Value = (Proposal, MultisigState)> {
+        (
+            1u8..=3,
+            any::<bool>(),
+            prop::option::of((prop::sample::select(vec![1u64, 100]), 1u32..=3)),
+            prop::option::of(time()),
+        )
+            .prop_map(|(threshold, draft, recurrence, expires_at)| {
+                let state = MultisigState::new([0u8; 32], threshold, ACTORS[..3].to_vec());
+                let mut proposal = Proposal::new_config(
+                    1,
+                    ACTORS[0],
+                    [0u8; 32],
+                    ConfigAction::ChangeThreshold { new_threshold: 1 },
+                    String::new(),
+                    None,
+                );
+                if draft {
+                    proposal.status = ProposalStatus::Draft;
+                }
+                proposal.recurrence = recurrence
+                    .map(|(interval, max_runs)| Recurrence { clock_account_id: CLOCK, interval, max_runs });
+                proposal.expiry = expires_at.map(|expires_at| Expiry { clock_account_id: CLOCK, expires_at });
+                (proposal, state)
+            })
+    }
+
+    /// Apply a config change the way Execute would, skipping ones it would refuse.
+    fn apply_config(state: &mut MultisigState, step: Step) {
+        match step {
+            Step::AddMember(m) if !state.is_member(&m) => state.members.push(m),
+            Step::RemoveMember(m) if state.is_member(&m) && state.members.len() > state.threshold as usize => {
+                state.members.retain(|x| x != &m)
+            }
+            Step::ChangeThreshold(t) if (t as usize) <= state.members.len() => state.threshold = t,
+            Step::SetRejectThreshold(cutoff) => state.reject_threshold = cutoff,
+            _ => {}
+        }
+        state.member_count = state.members.len() as u8;
+    }
+
+    fn check_invariants(proposal: &Proposal, state: &MultisigState) {
+        for m in &proposal.approved {
+            assert!(!proposal.rejected.contains(m), "member both approved and rejected");
+            assert_eq!(proposal.approved.iter().filter(|x| *x == m).count(), 1, "duplicate approval");
+        }
+        for m in &proposal.rejected {
+            assert_eq!(proposal.rejected.iter().filter(|x| *x == m).count(), 1, "duplicate rejection");
+        }
+        assert!(proposal.approval_count(state) <= state.members.len());
+        assert!(proposal.rejection_count(state) <= state.members.len());
+        match &proposal.recurrence {
+            Some(recurrence) => {
+                assert!(proposal.runs_executed <= recurrence.max_runs, "ran more often than allowed");
+                assert_eq!(proposal.last_executed_at.is_some(), proposal.runs_executed > 0);
+            }
+            None => assert_eq!(proposal.runs_executed, 0),
+        }
+        if proposal.status == ProposalStatus::Expired {
+            assert!(proposal.expiry.is_some(), "expired without a deadline");
+        }
+    }
+
+    fn step(proposal: &mut Proposal, state: &mut MultisigState, step: Step) {
+        let event = match step {
+            Step::Event(event) => event,
+            config => return apply_config(state, config),
+        };
+
+        let snapshot = |p: &Proposal| (p.approved.clone(), p.rejected.clone(), p.status, p.runs_executed, p.last_executed_at);
+        let before = snapshot(proposal);
+        let was = proposal.status;
+        let had_threshold = proposal.has_threshold(state);
+        let expires_at = proposal.expiry.as_ref().map(|e| e.expires_at);
+
+        match proposal.apply(state, event) {
+            Ok(status) => {
+                assert!(
+                    matches!(was, ProposalStatus::Active | ProposalStatus::Draft),
+                    "final status left via {:?}",
+                    event
+                );
+                assert_eq!(status, proposal.status);
+                if was == ProposalStatus::Draft {
+                    assert!(matches!(
+                        event,
+                        ProposalEvent::Activate(_) | ProposalEvent::Cancel(_) | ProposalEvent::Expire { .. }
+                    ));
+                }
+                match event {
+                    ProposalEvent::Activate(by) => {
+                        assert_eq!(by, proposal.proposer);
+                        assert_eq!(status, ProposalStatus::Active);
+                    }
+                    ProposalEvent::Approve(_) => assert_eq!(status, ProposalStatus::Active),
+                    ProposalEvent::Reject(_) => {
+                        let killed = proposal.is_dead(state) || proposal.is_vetoed(state);
+                        assert_eq!(status == ProposalStatus::Rejected, killed)
+                    }
+                    ProposalEvent::Execute { now, .. } => {
+                        assert!(had_threshold);
+                        if let Some(expires_at) = expires_at {
+                            assert!(now.is_some_and(|now| now < expires_at), "executed past the deadline");
+                        }
+                        match &proposal.recurrence {
+                            None => assert_eq!(status, ProposalStatus::Executed),
+                            Some(recurrence) => {
+                                let (_, _, _, runs_before, last_before) = before;
+                                assert_eq!(proposal.runs_executed, runs_before + 1);
+                                assert_eq!(proposal.last_executed_at, now);
+                                if let (Some(last), Some(now)) = (last_before, now) {
+                                    assert!(now >= last + recurrence.interval, "ran before the interval passed");
+                                }
+                                let last_run = proposal.runs_executed == recurrence.max_runs;
+                                assert_eq!(status == ProposalStatus::Executed, last_run);
+                                assert_eq!(status == ProposalStatus::Active, !last_run);
+                            }
+                        }
+                    }
+                    ProposalEvent::Cancel(by) => {
+                        assert_eq!(by, proposal.proposer);
+                        assert_eq!(status, ProposalStatus::Cancelled);
+                    }
+                    ProposalEvent::Expire { now } => {
+                        assert!(expires_at.is_some_and(|expires_at| now >= expires_at));
+                        assert_eq!(status, ProposalStatus::Expired);
+                    }
+                }
+            }
+            Err(err) => {
+                assert_eq!(snapshot(proposal), before, "failed {:?} modified the proposal", event);
+                match (was, event) {
+                    (
+                        ProposalStatus::Draft,
+                        ProposalEvent::Activate(_) | ProposalEvent::Cancel(_) | ProposalEvent::Expire { .. },
+                    ) => {}
+                    (ProposalStatus::Draft, _) => assert_eq!(err, LifecycleError::NotActive),
+                    (_, ProposalEvent::Activate(_)) => assert_eq!(err, LifecycleError::NotDraft),
+                    (ProposalStatus::Active, _) => {}
+                    _ => assert_eq!(err, LifecycleError::NotActive),
+                }
+                match (err, event) {
+                    (LifecycleError::NoExpiry, _) => assert_eq!(expires_at, None),
+                    (LifecycleError::NotExpired { .. }, ProposalEvent::Expire { now }) => {
+                        assert!(expires_at.is_some_and(|expires_at| now < expires_at))
+                    }
+                    (LifecycleError::Expired { .. }, ProposalEvent::Execute { now, .. }) => {
+                        assert!(expires_at.zip(now).is_some_and(|(expires_at, now)| now >= expires_at))
+                    }
+                    (LifecycleError::MissingClock, ProposalEvent::Execute { now, .. }) => {
+                        assert!(now.is_none() && proposal.clock_account_id().is_some())
+                    }
+                    _ => {}
+                }
+            }
+        }
+        check_invariants(proposal, state);
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(CASES))]
+
+        /// Any history of up to `MAX_STEPS` votes, executions at arbitrary
+        /// clock times, expiry attempts and config changes keeps the lifecycle
+        /// invariants, for recurring, expiring, active and draft proposals.
+        #[test]
+        fn test_lifecycle_invariants(
+            (mut proposal, mut state) in any_proposal(),
+            steps in prop::collection::vec(any_step(), 0..=MAX_STEPS),
+        ) {
+            for s in steps {
+                step(&mut proposal, &mut state, s);
+            }
+        }
+    }
+
+    #[test]
+    fn test_recurring_proposal_runs_once_per_interval() {
+        let state = MultisigState::new([0u8; 32], 1, vec![[1u8; 32]]);
+        let mut proposal = Proposal::new(
+            1,
+            [1u8; 32],
+            [0u8; 32],
+            [42u32; 8],
+            vec![0u32],
+            vec![],
+            vec![],
+            vec![],
+            String::new(),
+            None,
+        );
+        proposal.recurrence = Some(Recurrence { clock_account_id: [9u8; 32], interval: 100, max_runs: 2 });
+        let execute = |now| ProposalEvent::Execute { executor: [1u8; 32], now };
+
+        assert_eq!(proposal.apply(&state, execute(None)), Err(LifecycleError::MissingClock));
+        assert_eq!(proposal.apply(&state, execute(Some(1_000))), Ok(ProposalStatus::Active));
+        assert_eq!(proposal.remaining_runs(), Some(1));
+        assert_eq!(proposal.apply(&state, execute(Some(1_099))), Err(LifecycleError::NotDue { next_at: 1_100 }));
+        assert_eq!(proposal.apply(&state, execute(Some(1_100))), Ok(ProposalStatus::Executed));
+        assert_eq!(proposal.remaining_runs(), Some(0));
+        assert_eq!(proposal.apply(&state, execute(Some(9_999))), Err(LifecycleError::NotActive));
+    }
+
+    #[test]
+    fn test_proposal_expires_at_deadline() {
+        let state = MultisigState::new([0u8; 32], 1, vec![[1u8; 32]]);
+        let mut proposal = Proposal::new_config(
+            1,
+            [1u8; 32],
+            [0u8; 32],
+            ConfigAction::ChangeThreshold { new_threshold: 1 },
+            String::new(),
+            None,
+        );
+        let execute = |now| ProposalEvent::Execute { executor: [1u8; 32], now };
+
+        assert_eq!(proposal.apply(&state, ProposalEvent::Expire { now: 1_000 }), Err(LifecycleError::NoExpiry));
+        proposal.expiry = Some(Expiry { clock_account_id: [9u8; 32], expires_at: 1_000 });
+
+        assert_eq!(proposal.apply(&state, execute(None)), Err(LifecycleError::MissingClock));
+        assert_eq!(proposal.apply(&state, execute(Some(1_000))), Err(LifecycleError::Expired { expires_at: 1_000 }));
+        assert_eq!(
+            proposal.apply(&state, ProposalEvent::Expire { now: 999 }),
+            Err(LifecycleError::NotExpired { expires_at: 1_000 })
+        );
+        assert_eq!(proposal.apply(&state, ProposalEvent::Expire { now: 1_000 }), Ok(ProposalStatus::Expired));
+        assert_eq!(proposal.apply(&state, ProposalEvent::Approve([1u8; 32])), Err(LifecycleError::NotActive));
+    }
+
+    #[test]
+    fn test_is_dead_with_rejections_from_removed_members() {
+        let state = MultisigState::new([0u8; 32], 1, vec![[1u8; 32]]);
+        let mut proposal = Proposal::new_config(
+            1,
+            [1u8; 32],
+            [0u8; 32],
+            ConfigAction::ChangeThreshold { new_threshold: 1 },
+            String::new(),
+            None,
+        );
+        proposal.rejected = vec![[2u8; 32], [3u8; 32]];
+
+        assert!(!proposal.is_dead(&state));
+    }
+}
@@ -0,0 +1,105 @@
+// ActivateProposal handler — opens a Draft proposal for voting.
+//
+// A draft lets co-signers inspect the exact on-chain proposal bytes before any
+// votes are cast. Only the proposer can activate it.
+//
+// Expected accounts:
+// - accounts[0]: multisig_state PDA (read membership)
+// - accounts[1]: proposer (must be authorized signer, must still be a member)
+// - accounts[2]: proposal PDA account (owned by multisig program, status Draft)
+
+use nssa_core::account::AccountWithMetadata;
+use nssa_core::program::{AccountPostState, ChainedCall};
+use multisig_core::{MultisigState, Proposal, ProposalEvent};
+
+pub fn handle(
+    accounts: &[AccountWithMetadata],
+    _proposal_index: u64,
+) -> (Vec<AccountPostState>, Vec<ChainedCall>) {
+    assert!(accounts.len() >= 3, "ActivateProposal requires multisig_state + proposer + proposal accounts");
+
+    let multisig_account = &accounts[0];
+    let proposer_account = &accounts[1];
+    let proposal_account = &accounts[2];
+
+    assert!(proposer_account.is_authorized, "Proposer must sign the transaction");
+
+    let state_data: Vec<u8> = multisig_account.account.data.clone().into();
+    let state = MultisigState::from_bytes(&state_data)
+        .expect("Failed to deserialize multisig state");
+
+    let proposal_data: Vec<u8> = proposal_account.account.data.clone().into();
+    let mut proposal = Proposal::from_bytes(&proposal_data)
+        .expect("Failed to deserialize proposal");
+
+    assert_eq!(proposal.multisig_create_key, state.create_key, "Proposal does not belong to this multisig");
+    crate::check_multisig_accounts(multisig_account, proposal_account, &state, &proposal);
+
+    let proposer_id = *proposer_account.account_id.value();
+    if let Err(e) = proposal.apply(&state, ProposalEvent::Activate(proposer_id)) {
+        panic!("{}", e);
+    }
+
+    let mut proposal_post = proposal_account.account.clone();
+    proposal_post.data = proposal.to_bytes().try_into().unwrap();
+
+    (
+        vec![
+            AccountPostState::new(multisig_account.account.clone()),
+            AccountPostState::new(proposer_account.account.clone()),
+            AccountPostState::new(proposal_post),
+        ],
+        vec![],
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+    use multisig_core::ProposalStatus;
+
+    fn make_draft(proposer: [u8; 32]) -> Vec<u8> {
+        let mut proposal = new_proposal(proposer);
+        proposal.memo = "Payment batch 2026-10".to_string();
+        proposal.status = ProposalStatus::Draft;
+        proposal.to_bytes()
+    }
+
+    fn make_accounts(signer: [u8; 32], proposal_data: Vec<u8>) -> Vec<AccountWithMetadata> {
+        vec![
+            make_account(&state_id(), make_state_with_proposal(2, vec![[1u8; 32], [2u8; 32]]), false),
+            make_account(&signer, vec![], true),
+            make_account(&proposal_id(1), proposal_data, false),
+        ]
+    }
+
+    #[test]
+    fn test_activate_opens_draft_for_voting() {
+        let (post_states, _) = handle(&make_accounts([1u8; 32], make_draft([1u8; 32])), 1);
+
+        let proposal = Proposal::from_bytes(&Vec::from(post_states[2].account().data.clone())).unwrap();
+        assert_eq!(proposal.status, ProposalStatus::Active);
+        assert_eq!(proposal.approved, vec![[1u8; 32]]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Only the proposer")]
+    fn test_activate_by_other_member_fails() {
+        handle(&make_accounts([2u8; 32], make_draft([1u8; 32])), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "not a draft")]
+    fn test_activate_active_proposal_fails() {
+        let mut proposal = Proposal::from_bytes(&make_draft([1u8; 32])).unwrap();
+        proposal.status = ProposalStatus::Active;
+        handle(&make_accounts([1u8; 32], proposal.to_bytes()), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "Proposal is not active")]
+    fn test_approve_draft_fails() {
+        crate::approve::handle(&make_accounts([2u8; 32], make_draft([1u8; 32])), 1);
+    }
+}
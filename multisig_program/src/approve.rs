@@ -2,12 +2,13 @@
 //
 // Expected accounts:
 // - accounts[0]: multisig_state PDA (read membership)
-// - accounts[1]: approver account (must be authorized = is a signer)
+// - accounts[1]: approver account (must be authorized = is a signer, or a nested
+//   multisig's vault PDA authorized via the child's ChainedCall pda_seeds)
 // - accounts[2]: proposal PDA account (owned by multisig program)
 
 use nssa_core::account::AccountWithMetadata;
 use nssa_core::program::{AccountPostState, ChainedCall};
-use multisig_core::{MultisigState, Proposal, ProposalStatus};
+use multisig_core::{MultisigState, Proposal, ProposalEvent};
 
 pub fn handle(
     accounts: &[AccountWithMetadata],
@@ -23,7 +24,7 @@ pub fn handle(
 
     // Read multisig state for membership check
     let state_data: Vec<u8> = multisig_account.account.data.clone().into();
-    let state: MultisigState = borsh::from_slice(&state_data)
+    let state = MultisigState::from_bytes(&state_data)
         .expect("Failed to deserialize multisig state");
 
     let approver_id = *approver_account.account_id.value();
@@ -31,17 +32,18 @@ pub fn handle(
 
     // Read and update proposal
     let proposal_data: Vec<u8> = proposal_account.account.data.clone().into();
-    let mut proposal: Proposal = borsh::from_slice(&proposal_data)
+    let mut proposal = Proposal::from_bytes(&proposal_data)
         .expect("Failed to deserialize proposal");
 
     assert_eq!(proposal.multisig_create_key, state.create_key, "Proposal does not belong to this multisig");
-    assert_eq!(proposal.status, ProposalStatus::Active, "Proposal is not active");
-
-    let is_new = proposal.approve(approver_id);
-    assert!(is_new, "Member has already approved this proposal");
+    crate::check_multisig_accounts(multisig_account, proposal_account, &state, &proposal);
+    crate::check_not_migrated(&state, &proposal);
+    if let Err(e) = proposal.apply(&state, ProposalEvent::Approve(approver_id)) {
+        panic!("{}", e);
+    }
 
     // Write back proposal
-    let proposal_bytes = borsh::to_vec(&proposal).unwrap();
+    let proposal_bytes = proposal.to_bytes();
     let mut proposal_post = proposal_account.account.clone();
     proposal_post.data = proposal_bytes.try_into().unwrap();
 
@@ -62,56 +64,42 @@ pub fn handle(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use nssa_core::account::{Account, AccountId};
+    use crate::test_utils::*;
+    use nssa_core::account::Account;
     use nssa_core::program::ProgramId;
     use multisig_core::MultisigState;
 
-    fn make_account(id: &[u8; 32], data: Vec<u8>, authorized: bool) -> AccountWithMetadata {
-        let mut account = Account::default();
-        account.data = data.try_into().unwrap();
-        AccountWithMetadata {
-            account_id: AccountId::new(*id),
-            account,
-            is_authorized: authorized,
-        }
-    }
+    #[test]
+    #[should_panic(expected = "migrated to another program")]
+    fn test_approve_after_migration_fails() {
+        let mut state = MultisigState::new([0u8; 32], 2, vec![[1u8; 32], [2u8; 32]]);
+        state.transaction_index = 1;
+        state.migrated_to = Some([9u32; 8]);
 
-    fn make_multisig_state(threshold: u8, members: Vec<[u8; 32]>) -> Vec<u8> {
-        let mut state = MultisigState::new([0u8; 32], threshold, members);
-        state.transaction_index = 1; // proposal exists
-        borsh::to_vec(&state).unwrap()
-    }
+        let accounts = vec![
+            make_account(&state_id(), state.to_bytes(), false),
+            make_account(&[2u8; 32], vec![], true),
+            make_account(&proposal_id(1), make_proposal([1u8; 32]), false),
+        ];
 
-    fn make_proposal(proposer: [u8; 32]) -> Vec<u8> {
-        let fake_program_id: ProgramId = [42u32; 8];
-        let proposal = Proposal::new(
-            1,
-            proposer,
-            [0u8; 32], // create_key matches multisig
-            fake_program_id,
-            vec![0u32],
-            1,
-            vec![],
-            vec![],
-        );
-        borsh::to_vec(&proposal).unwrap()
+        handle(&accounts, 1);
     }
 
     #[test]
     fn test_approve_adds_approval() {
         let members = vec![[1u8; 32], [2u8; 32], [3u8; 32]];
-        let state_data = make_multisig_state(2, members);
+        let state_data = make_state_with_proposal(2, members);
         let proposal_data = make_proposal([1u8; 32]);
 
         let accounts = vec![
-            make_account(&[10u8; 32], state_data, false),
+            make_account(&state_id(), state_data, false),
             make_account(&[2u8; 32], vec![], true),
-            make_account(&[20u8; 32], proposal_data, false),
+            make_account(&proposal_id(1), proposal_data, false),
         ];
 
         let (post_states, _) = handle(&accounts, 1);
 
-        let proposal: Proposal = borsh::from_slice(&Vec::from(post_states[2].account().data.clone())).unwrap();
+        let proposal = Proposal::from_bytes(&Vec::from(post_states[2].account().data.clone())).unwrap();
         assert_eq!(proposal.approved.len(), 2);
         assert!(proposal.approved.contains(&[1u8; 32]));
         assert!(proposal.approved.contains(&[2u8; 32]));
@@ -121,15 +109,85 @@ mod tests {
     #[should_panic(expected = "already approved")]
     fn test_approve_duplicate_fails() {
         let members = vec![[1u8; 32], [2u8; 32]];
-        let state_data = make_multisig_state(2, members);
+        let state_data = make_state_with_proposal(2, members);
         let proposal_data = make_proposal([1u8; 32]);
 
         let accounts = vec![
-            make_account(&[10u8; 32], state_data, false),
+            make_account(&state_id(), state_data, false),
             make_account(&[1u8; 32], vec![], true),
-            make_account(&[20u8; 32], proposal_data, false),
+            make_account(&proposal_id(1), proposal_data, false),
         ];
 
         handle(&accounts, 1);
     }
+
+    #[test]
+    #[should_panic(expected = "not owned by this program")]
+    fn test_approve_with_foreign_state_account_fails() {
+        let members = vec![[1u8; 32], [2u8; 32]];
+        let mut state_account = make_account(&state_id(), make_state_with_proposal(2, members), false);
+        state_account.account.program_owner = [66u32; 8];
+
+        let accounts = vec![
+            state_account,
+            make_account(&[2u8; 32], vec![], true),
+            make_account(&proposal_id(1), make_proposal([1u8; 32]), false),
+        ];
+
+        handle(&accounts, 1);
+    }
+
+    #[test]
+    fn test_approve_by_nested_multisig_via_chained_call() {
+        use multisig_core::{
+            compute_multisig_state_pda, compute_proposal_pda, compute_vault_pda, nested_vote_target_account_ids,
+            vault_pda_seed_bytes, NESTED_VOTE_VOTER_INDEX,
+        };
+
+        // Test accounts keep the default owner, which stands in for this program's ID
+        let program_id: ProgramId = Account::default().program_owner;
+        let parent_key = [0u8; 32];
+        let child_key = [5u8; 32];
+        let child_vault = *compute_vault_pda(&program_id, &child_key).value();
+
+        // Parent: 2-of-2 with a human member and the child's vault
+        let parent_state = make_state_with_proposal(2, vec![[1u8; 32], child_vault]);
+        let parent_proposal = make_proposal([1u8; 32]);
+
+        // Child: 1-of-1 proposal voting on parent proposal #1
+        let target_ids = nested_vote_target_account_ids(&program_id, &parent_key, 1, &child_key);
+        let child_proposal = Proposal::new(
+            1,
+            [3u8; 32],
+            child_key,
+            program_id,
+            vec![0u32],
+            target_ids.clone(),
+            vec![vault_pda_seed_bytes(&child_key)],
+            vec![NESTED_VOTE_VOTER_INDEX],
+            String::new(),
+            None,
+        );
+        let child_state = MultisigState::new(child_key, 1, vec![[3u8; 32]]).to_bytes();
+
+        let child_accounts = vec![
+            make_account(compute_multisig_state_pda(&program_id, &child_key).value(), child_state, false),
+            make_account(&[3u8; 32], vec![], true),
+            make_account(compute_proposal_pda(&program_id, &child_key, 1).value(), child_proposal.to_bytes(), false),
+            make_account(&target_ids[0], parent_state, false),
+            make_account(&target_ids[1], vec![], false),
+            make_account(&target_ids[2], parent_proposal, false),
+        ];
+
+        let (_, chained) = crate::execute::handle(&child_accounts, 1, &[]);
+        assert_eq!(chained.len(), 1);
+        assert_eq!(chained[0].program_id, program_id);
+
+        // The parent's Approve runs on the chained call's pre-states
+        let (post_states, _) = handle(&chained[0].pre_states, 1);
+
+        let proposal = Proposal::from_bytes(&Vec::from(post_states[2].account().data.clone())).unwrap();
+        assert!(proposal.approved.contains(&child_vault));
+        assert!(proposal.approved.len() >= 2);
+    }
 }
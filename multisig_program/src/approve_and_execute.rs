@@ -0,0 +1,254 @@
+// ApproveAndExecute handler — approves a proposal and, if that approval brings it
+// to threshold, executes it in the same transaction.
+//
+// Saves a round-trip for small multisigs (2-of-2, 2-of-3) where the final approver
+// would otherwise have to send a separate Execute.
+//
+// A recurring proposal whose next run is not due yet, or a proposal whose
+// dependency has not been executed yet, is only approved, like a proposal
+// below threshold, so the approval is not lost with a failed execute.
+//
+// Expected accounts:
+// - accounts[0]: multisig_state PDA (read membership/threshold)
+// - accounts[1]: approver (must be authorized signer, must be member)
+// - accounts[2]: proposal PDA account (owned by multisig program)
+// - accounts[3..]: target accounts to pass to the ChainedCall (must match proposal.target_account_ids)
+
+use nssa_core::account::AccountWithMetadata;
+use nssa_core::program::{AccountPostState, ChainedCall};
+use multisig_core::{ExpectedAccount, MultisigState, Proposal};
+
+use crate::{approve, execute};
+
+pub fn handle(
+    accounts: &[AccountWithMetadata],
+    proposal_index: u64,
+    post_states: &[ExpectedAccount],
+) -> (Vec<AccountPostState>, Vec<ChainedCall>) {
+    assert!(accounts.len() >= 3, "ApproveAndExecute requires multisig_state + approver + proposal accounts");
+
+    let (mut approve_post_states, _) = approve::handle(&accounts[..3], proposal_index);
+
+    let state_data: Vec<u8> = accounts[0].account.data.clone().into();
+    let state = MultisigState::from_bytes(&state_data)
+        .expect("Failed to deserialize multisig state");
+
+    let proposal_data: Vec<u8> = approve_post_states[2].account().data.clone().into();
+    let proposal = Proposal::from_bytes(&proposal_data)
+        .expect("Failed to deserialize proposal");
+
+    if !proposal.has_threshold(&state)
+        || !execute::is_due(accounts, &proposal)
+        || !execute::dependency_executed(accounts, &proposal)
+    {
+        // Threshold not reached, next run not due or dependency pending — behave like a plain Approve
+        for target in &accounts[3..] {
+            approve_post_states.push(AccountPostState::new(target.account.clone()));
+        }
+        return (approve_post_states, vec![]);
+    }
+
+    // Execute against the proposal as updated by this approval
+    let mut execute_accounts = accounts.to_vec();
+    execute_accounts[2].account = approve_post_states[2].account().clone();
+    execute::handle(&execute_accounts, proposal_index, post_states)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+    use multisig_core::{ConfigAction, ProposalStatus, Recurrence};
+
+    #[test]
+    fn test_approve_and_execute_reaching_threshold_emits_chained_call() {
+        let members = vec![[1u8; 32], [2u8; 32], [3u8; 32]];
+        let state_data = make_state_with_proposal(2, members);
+        let proposal_data = make_proposal([1u8; 32]);
+
+        let accounts = vec![
+            make_account(&state_id(), state_data, false),
+            make_account(&[2u8; 32], vec![], true),
+            make_account(&proposal_id(1), proposal_data, false),
+            make_account(&[30u8; 32], vec![], false),
+        ];
+
+        let (post_states, chained) = handle(&accounts, 1, &[]);
+
+        assert_eq!(chained.len(), 1);
+        assert_eq!(post_states.len(), 4);
+        assert!(chained[0].pre_states[0].is_authorized);
+
+        let proposal = Proposal::from_bytes(
+            &Vec::from(post_states[2].account().data.clone())
+        ).unwrap();
+        assert_eq!(proposal.status, ProposalStatus::Executed);
+        assert!(proposal.approved.contains(&[2u8; 32]));
+    }
+
+    #[test]
+    fn test_approve_and_execute_below_threshold_only_approves() {
+        let members = vec![[1u8; 32], [2u8; 32], [3u8; 32]];
+        let state_data = make_state_with_proposal(3, members);
+        let proposal_data = make_proposal([1u8; 32]);
+
+        let accounts = vec![
+            make_account(&state_id(), state_data, false),
+            make_account(&[2u8; 32], vec![], true),
+            make_account(&proposal_id(1), proposal_data, false),
+            make_account(&[30u8; 32], vec![], false),
+        ];
+
+        let (post_states, chained) = handle(&accounts, 1, &[]);
+
+        assert!(chained.is_empty());
+        assert_eq!(post_states.len(), 4);
+
+        let proposal = Proposal::from_bytes(
+            &Vec::from(post_states[2].account().data.clone())
+        ).unwrap();
+        assert_eq!(proposal.status, ProposalStatus::Active);
+        assert_eq!(proposal.approved.len(), 2);
+    }
+
+    #[test]
+    fn test_approve_and_execute_config_proposal() {
+        let members = vec![[1u8; 32], [2u8; 32]];
+        let state_data = make_state_with_proposal(2, members);
+        let proposal = Proposal::new_config(
+            1,
+            [1u8; 32],
+            [0u8; 32],
+            ConfigAction::AddMember { new_member: [3u8; 32] },
+            String::new(),
+            None,
+        );
+
+        let accounts = vec![
+            make_account(&state_id(), state_data, false),
+            make_account(&[2u8; 32], vec![], true),
+            make_account(&proposal_id(1), proposal.to_bytes(), false),
+        ];
+
+        let (post_states, chained) = handle(&accounts, 1, &[]);
+
+        assert!(chained.is_empty());
+        let state = MultisigState::from_bytes(
+            &Vec::from(post_states[0].account().data.clone())
+        ).unwrap();
+        assert_eq!(state.member_count, 3);
+    }
+
+    #[test]
+    fn test_approve_and_execute_recurring_not_due_only_approves() {
+        let members = vec![[1u8; 32], [2u8; 32], [3u8; 32]];
+        let state_data = make_state_with_proposal(2, members);
+        let mut proposal = Proposal::from_bytes(&make_proposal([1u8; 32])).unwrap();
+        proposal.recurrence = Some(Recurrence { clock_account_id: [50u8; 32], interval: 100, max_runs: 3 });
+        proposal.runs_executed = 1;
+        proposal.last_executed_at = Some(1_000);
+
+        let accounts = vec![
+            make_account(&state_id(), state_data, false),
+            make_account(&[2u8; 32], vec![], true),
+            make_account(&proposal_id(1), proposal.to_bytes(), false),
+            make_account(&[50u8; 32], 1_050u64.to_le_bytes().to_vec(), false),
+            make_account(&[30u8; 32], vec![], false),
+        ];
+
+        let (post_states, chained) = handle(&accounts, 1, &[]);
+
+        assert!(chained.is_empty());
+        assert_eq!(post_states.len(), 5);
+        let proposal = Proposal::from_bytes(
+            &Vec::from(post_states[2].account().data.clone())
+        ).unwrap();
+        assert!(proposal.approved.contains(&[2u8; 32]));
+        assert_eq!(proposal.runs_executed, 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "migrated to another program")]
+    fn test_approve_and_execute_after_migration_fails() {
+        let mut state = MultisigState::new([0u8; 32], 2, vec![[1u8; 32], [2u8; 32]]);
+        state.transaction_index = 1;
+        state.migrated_to = Some([9u32; 8]);
+
+        let accounts = vec![
+            make_account(&state_id(), state.to_bytes(), false),
+            make_account(&[2u8; 32], vec![], true),
+            make_account(&proposal_id(1), make_proposal([1u8; 32]), false),
+            make_account(&[30u8; 32], vec![], false),
+        ];
+
+        handle(&accounts, 1, &[]);
+    }
+
+    #[test]
+    fn test_approve_and_execute_dependency_pending_only_approves() {
+        let members = vec![[1u8; 32], [2u8; 32], [3u8; 32]];
+        let state_data = make_state_with_proposal(2, members);
+        let mut proposal = new_proposal([1u8; 32]);
+        proposal.index = 2;
+        proposal.depends_on = Some(1);
+        let dependency = new_proposal([1u8; 32]);
+
+        let accounts = vec![
+            make_account(&state_id(), state_data, false),
+            make_account(&[2u8; 32], vec![], true),
+            make_account(&proposal_id(2), proposal.to_bytes(), false),
+            make_account(&proposal_id(1), dependency.to_bytes(), false),
+            make_account(&[30u8; 32], vec![], false),
+        ];
+
+        let (post_states, chained) = handle(&accounts, 2, &[]);
+
+        assert!(chained.is_empty());
+        assert_eq!(post_states.len(), 5);
+        let proposal = Proposal::from_bytes(
+            &Vec::from(post_states[2].account().data.clone())
+        ).unwrap();
+        assert_eq!(proposal.status, ProposalStatus::Active);
+        assert!(proposal.approved.contains(&[2u8; 32]));
+    }
+
+    #[test]
+    fn test_approve_and_execute_after_dependency_executed() {
+        let members = vec![[1u8; 32], [2u8; 32], [3u8; 32]];
+        let state_data = make_state_with_proposal(2, members);
+        let mut proposal = new_proposal([1u8; 32]);
+        proposal.index = 2;
+        proposal.depends_on = Some(1);
+        let mut dependency = new_proposal([1u8; 32]);
+        dependency.status = ProposalStatus::Executed;
+
+        let accounts = vec![
+            make_account(&state_id(), state_data, false),
+            make_account(&[2u8; 32], vec![], true),
+            make_account(&proposal_id(2), proposal.to_bytes(), false),
+            make_account(&proposal_id(1), dependency.to_bytes(), false),
+            make_account(&[30u8; 32], vec![], false),
+        ];
+
+        let (_, chained) = handle(&accounts, 2, &[]);
+
+        assert_eq!(chained.len(), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "already approved")]
+    fn test_approve_and_execute_duplicate_approval_fails() {
+        let members = vec![[1u8; 32], [2u8; 32]];
+        let state_data = make_state_with_proposal(2, members);
+        let proposal_data = make_proposal([1u8; 32]);
+
+        let accounts = vec![
+            make_account(&state_id(), state_data, false),
+            make_account(&[1u8; 32], vec![], true),
+            make_account(&proposal_id(1), proposal_data, false),
+            make_account(&[30u8; 32], vec![], false),
+        ];
+
+        handle(&accounts, 1, &[]);
+    }
+}
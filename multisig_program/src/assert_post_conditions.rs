@@ -0,0 +1,117 @@
+// AssertPostConditions handler — checks a proposal's post-conditions after its
+// ChainedCall has run.
+//
+// Execute emits this as a second ChainedCall back into this program, right
+// after the proposal's own call, carrying the conditions and the values they
+// read before the call. Its accounts are the states the executor expects after
+// that call (see `ExpectedAccount` for how the runtime holds them to the real
+// ones). A failed check panics and aborts the whole transaction, so the approved
+// call's effects never land. The handler only reads, so calling it directly is
+// harmless.
+//
+// Expected accounts:
+// - the target accounts the conditions refer to, in
+//   `post_condition_account_indices` order, as they are after the proposal's call
+
+use nssa_core::account::AccountWithMetadata;
+use nssa_core::program::{AccountPostState, ChainedCall};
+use multisig_core::{PostCondition, post_condition_account_indices};
+
+pub fn handle(
+    accounts: &[AccountWithMetadata],
+    conditions: &[PostCondition],
+    snapshots: &[u128],
+) -> (Vec<AccountPostState>, Vec<ChainedCall>) {
+    assert_eq!(conditions.len(), snapshots.len(), "Expected one snapshot per post-condition");
+    let indices = post_condition_account_indices(conditions);
+    assert_eq!(
+        accounts.len(),
+        indices.len(),
+        "Expected one account per target account the post-conditions refer to"
+    );
+
+    for (condition, snapshot) in conditions.iter().zip(snapshots) {
+        let position = indices.binary_search(&condition.account_index()).unwrap();
+        if let Err(e) = condition.check(*snapshot, &accounts[position].account) {
+            panic!("{}", e);
+        }
+    }
+
+    let post_states = accounts
+        .iter()
+        .map(|acc| AccountPostState::new(acc.account.clone()))
+        .collect();
+    (post_states, vec![])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nssa_core::account::{Account, AccountId};
+
+    fn make_account(id: &[u8; 32], balance: u128) -> AccountWithMetadata {
+        let mut account = Account::default();
+        account.balance = balance;
+        AccountWithMetadata {
+            account_id: AccountId::new(*id),
+            account,
+            is_authorized: false,
+        }
+    }
+
+    #[test]
+    fn test_decrease_within_limit_passes() {
+        let accounts = vec![make_account(&[30u8; 32], 900), make_account(&[31u8; 32], 100)];
+        let conditions = [
+            PostCondition::MaxBalanceDecrease { account_index: 0, max_decrease: 100 },
+            PostCondition::AccountExists { account_index: 1 },
+        ];
+
+        let (post_states, chained) = handle(&accounts, &conditions, &[1_000, 0]);
+        assert_eq!(post_states.len(), 2);
+        assert!(chained.is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "Target account 0 decreased by 500, more than the approved 100")]
+    fn test_decrease_over_limit_fails() {
+        let accounts = vec![make_account(&[30u8; 32], 500)];
+        let conditions = [PostCondition::MaxBalanceDecrease { account_index: 0, max_decrease: 100 }];
+        handle(&accounts, &conditions, &[1_000]);
+    }
+
+    #[test]
+    fn test_token_balance_in_data() {
+        // Fungible token holding: variant tag, definition ID, then a u128 balance
+        let mut holding = vec![0u8; 33];
+        holding.extend_from_slice(&700u128.to_le_bytes());
+        let mut account = make_account(&[30u8; 32], 0);
+        account.account.data = holding.try_into().unwrap();
+        let condition = PostCondition::MaxDataDecrease { account_index: 0, offset: 33, max_decrease: 300 };
+
+        handle(std::slice::from_ref(&account), &[condition.clone()], &[1_000]);
+        let result = std::panic::catch_unwind(|| handle(&[account], &[condition], &[1_001]));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_conditions_read_their_own_account() {
+        // Only target accounts 1 and 3 are referred to, so only they are passed
+        let accounts = vec![make_account(&[31u8; 32], 100), make_account(&[33u8; 32], 50)];
+        let conditions = [
+            PostCondition::MaxBalanceDecrease { account_index: 3, max_decrease: 0 },
+            PostCondition::MaxBalanceDecrease { account_index: 1, max_decrease: 0 },
+        ];
+
+        handle(&accounts, &conditions, &[50, 100]);
+        let result = std::panic::catch_unwind(|| handle(&accounts, &conditions, &[100, 50]));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    #[should_panic(expected = "Target account 0 does not exist after execution")]
+    fn test_missing_account_fails() {
+        let accounts = vec![make_account(&[31u8; 32], 0)];
+        handle(&accounts, &[PostCondition::AccountExists { account_index: 0 }], &[0]);
+    }
+}
@@ -63,7 +63,7 @@ pub fn handle(
     let state = MultisigState::new(*create_key, threshold, members.to_vec());
     
     let mut multisig_account = Account::default();
-    let state_bytes = borsh::to_vec(&state).unwrap();
+    let state_bytes = state.to_bytes();
     multisig_account.data = state_bytes.try_into().unwrap();
     
     // Build post_states: claim multisig_state + all member accounts
@@ -109,7 +109,7 @@ mod tests {
         assert_eq!(post_states.len(), 4);
 
         // Verify multisig state was written correctly
-        let state: MultisigState = borsh::from_slice(
+        let state = MultisigState::from_bytes(
             &Vec::from(post_states[0].account().data.clone())
         ).unwrap();
         assert_eq!(state.threshold, 2);
@@ -5,60 +5,113 @@
 //
 // Expected accounts:
 // - accounts[0]: multisig_state PDA (read threshold/membership)
-// - accounts[1]: executor (must be authorized signer, must be member)
+// - accounts[1]: executor (must be authorized signer; must be a member unless the
+//   multisig has permissionless execution enabled)
 // - accounts[2]: proposal PDA account (owned by multisig program)
-// - accounts[3..]: target accounts to pass to the ChainedCall
+// - next, only if proposal.depends_on is set: the dependency's proposal PDA
+//   (must be Executed)
+// - next, only if proposal.recurrence or proposal.expiry is set: the approved
+//   clock account
+// - remaining: target accounts to pass to the ChainedCall(s)
+//   (must match proposal.expected_target_account_ids())
+//
+// A recurring proposal stays Active after each run until its last run.
+//
+// If the proposal has post-conditions, a second ChainedCall back into this
+// program (`AssertPostConditions`) checks the target accounts after the first
+// one has run, aborting the transaction if the outcome exceeds what was approved.
+// Its accounts are the executor's `post_states`; see `ExpectedAccount` for why
+// they cannot be forged.
 
 use nssa_core::account::AccountWithMetadata;
-use nssa_core::program::{AccountPostState, ChainedCall, PdaSeed};
-use multisig_core::{ConfigAction, MultisigState, Proposal, ProposalStatus};
+use nssa_core::program::{AccountPostState, ChainedCall, PdaSeed, ProgramId};
+use multisig_core::{
+    ConfigAction, ExpectedAccount, HandoverCall, Instruction, MultisigState, PostCondition, Proposal,
+    ProposalEvent, ProposalStatus, clock_timestamp, compute_proposal_pda, multisig_state_pda_seed,
+    post_condition_account_indices, vault_pda_seed_bytes,
+};
 
 pub fn handle(
     accounts: &[AccountWithMetadata],
     _proposal_index: u64,
+    post_states: &[ExpectedAccount],
 ) -> (Vec<AccountPostState>, Vec<ChainedCall>) {
     assert!(accounts.len() >= 3, "Execute requires at least multisig_state + executor + proposal");
 
     let multisig_account = &accounts[0];
     let executor_account = &accounts[1];
     let proposal_account = &accounts[2];
-    let target_accounts = &accounts[3..];
 
     assert!(executor_account.is_authorized, "Executor must sign the transaction");
 
     // Read multisig state
     let state_data: Vec<u8> = multisig_account.account.data.clone().into();
-    let mut state: MultisigState = borsh::from_slice(&state_data)
+    let mut state = MultisigState::from_bytes(&state_data)
         .expect("Failed to deserialize multisig state");
 
     let executor_id = *executor_account.account_id.value();
-    assert!(state.is_member(&executor_id), "Executor is not a multisig member");
+    assert!(state.can_execute(&executor_id), "Executor is not a multisig member");
 
     // Read proposal
     let proposal_data: Vec<u8> = proposal_account.account.data.clone().into();
-    let mut proposal: Proposal = borsh::from_slice(&proposal_data)
+    let mut proposal = Proposal::from_bytes(&proposal_data)
         .expect("Failed to deserialize proposal");
 
     assert_eq!(proposal.multisig_create_key, state.create_key, "Proposal does not belong to this multisig");
-    assert_eq!(proposal.status, ProposalStatus::Active, "Proposal is not active");
-    assert!(
-        proposal.has_threshold(state.threshold),
-        "Proposal does not have enough approvals: need {}, have {}",
-        state.threshold,
-        proposal.approved.len()
-    );
+    let program_id = crate::check_multisig_accounts(multisig_account, proposal_account, &state, &proposal);
+    crate::check_not_migrated(&state, &proposal);
+    // Accounts after the proposal: [dependency?, clock?, targets...]
+    let mut target_accounts = &accounts[3..];
+    if let Some(dependency_index) = proposal.depends_on {
+        let (dependency_account, rest) = target_accounts
+            .split_first()
+            .expect("Execute requires the dependency proposal account");
+        check_dependency(&program_id, dependency_account, &state, dependency_index);
+        target_accounts = rest;
+    }
+    let now = match proposal.clock_account_id() {
+        Some(clock_account_id) => {
+            let (clock_account, rest) = target_accounts
+                .split_first()
+                .expect("Execute requires the clock account");
+            assert_eq!(
+                clock_account.account_id.value(),
+                &clock_account_id,
+                "Clock account does not match the approved proposal"
+            );
+            target_accounts = rest;
+            let clock_data: Vec<u8> = clock_account.account.data.clone().into();
+            Some(clock_timestamp(&clock_data).expect("Clock account does not hold a timestamp"))
+        }
+        None => None,
+    };
 
-    // Mark as executed
-    proposal.status = ProposalStatus::Executed;
+    // Checks the threshold and schedule, and marks the proposal Executed
+    // (recurring proposals only after their last run)
+    if let Err(e) = proposal.apply(&state, ProposalEvent::Execute { executor: executor_id, now }) {
+        panic!("{}", e);
+    }
 
-    // Handle config change vs transfer proposal
-    if let Some(config_action) = &proposal.config_action {
-        // Config change: modify MultisigState directly, no ChainedCall
+    // Members approved these exact accounts — the executor cannot swap them
+    let expected_target_ids = proposal.expected_target_account_ids();
+    assert_eq!(
+        target_accounts.len(),
+        expected_target_ids.len(),
+        "Expected {} target accounts, got {}",
+        expected_target_ids.len(),
+        target_accounts.len()
+    );
+    for (i, (target, expected_id)) in target_accounts.iter().zip(&expected_target_ids).enumerate() {
         assert_eq!(
-            target_accounts.len(), 0,
-            "Config change proposals should not have target accounts"
+            target.account_id.value(),
+            expected_id,
+            "Target account {} does not match the approved proposal",
+            i
         );
+    }
 
+    // Handle config change vs transfer proposal
+    let chained_calls = if let Some(config_action) = &proposal.config_action {
         match config_action {
             ConfigAction::AddMember { new_member } => {
                 assert!(!state.is_member(new_member), "Account is already a member");
@@ -74,6 +127,14 @@ pub fn handle(
                     state.member_count - 1,
                     state.threshold
                 );
+                if let Some(cutoff) = state.reject_threshold {
+                    assert!(
+                        cutoff < state.member_count,
+                        "Cannot remove member: would make member count ({}) less than reject threshold ({})",
+                        state.member_count - 1,
+                        cutoff
+                    );
+                }
                 state.members.retain(|m| m != member);
                 state.member_count -= 1;
             }
@@ -87,119 +148,274 @@ pub fn handle(
                 );
                 state.threshold = *new_threshold;
             }
+            ConfigAction::SetPermissionlessExecution { enabled } => {
+                state.permissionless_execution = *enabled;
+            }
+            ConfigAction::SetRejectThreshold { reject_threshold } => {
+                if let Some(cutoff) = reject_threshold {
+                    assert!(*cutoff >= 1, "Reject threshold must be at least 1");
+                    assert!(
+                        *cutoff <= state.member_count,
+                        "Reject threshold ({}) cannot exceed member count ({})",
+                        cutoff,
+                        state.member_count
+                    );
+                }
+                state.reject_threshold = *reject_threshold;
+            }
+            ConfigAction::MigrateToProgram { new_program_id, handover_calls } => {
+                return execute_migration(
+                    accounts,
+                    target_accounts,
+                    state,
+                    &proposal,
+                    new_program_id,
+                    handover_calls,
+                );
+            }
         }
-
-        // Write back updated state
-        let state_bytes = borsh::to_vec(&state).unwrap();
-        let mut multisig_post = multisig_account.account.clone();
-        multisig_post.data = state_bytes.try_into().unwrap();
-
-        let proposal_bytes = borsh::to_vec(&proposal).unwrap();
-        let mut proposal_post = proposal_account.account.clone();
-        proposal_post.data = proposal_bytes.try_into().unwrap();
-
-        let executor_post = executor_account.account.clone();
-
-        (
-            vec![
-                AccountPostState::new(multisig_post),
-                AccountPostState::new(executor_post),
-                AccountPostState::new(proposal_post),
-            ],
-            vec![],
-        )
+        // Config change: MultisigState was modified directly, no ChainedCall
+        vec![]
     } else {
         // Transfer proposal: emit ChainedCall
-        assert_eq!(
-            target_accounts.len(),
-            proposal.target_account_count as usize,
-            "Expected {} target accounts, got {}",
-            proposal.target_account_count,
-            target_accounts.len()
-        );
-
-        let target_program_id = proposal.target_program_id.clone();
-        let target_instruction_data = proposal.target_instruction_data.clone();
         let pda_seeds: Vec<PdaSeed> = proposal.pda_seeds.iter().map(|s| PdaSeed::new(*s)).collect();
-        let authorized_indices = proposal.authorized_indices.clone();
-
-        let proposal_bytes = borsh::to_vec(&proposal).unwrap();
-        let mut proposal_post = proposal_account.account.clone();
-        proposal_post.data = proposal_bytes.try_into().unwrap();
-
-        let chained_pre_states: Vec<AccountWithMetadata> = target_accounts
-            .iter()
-            .enumerate()
-            .map(|(i, acc)| {
-                let mut acc = acc.clone();
-                if authorized_indices.contains(&(i as u8)) {
-                    acc.is_authorized = true;
-                }
-                acc
-            })
-            .collect();
-
-        let chained_call = ChainedCall {
-            program_id: target_program_id,
-            instruction_data: target_instruction_data,
-            pre_states: chained_pre_states,
+
+        let mut calls = vec![ChainedCall {
+            program_id: proposal.target_program_id,
+            instruction_data: proposal.target_instruction_data.clone(),
+            pre_states: authorize(target_accounts, &proposal.authorized_indices),
             pda_seeds,
-        };
+        }];
+        if !proposal.post_conditions.is_empty() {
+            calls.push(post_condition_call(
+                program_id,
+                target_accounts,
+                &proposal.post_conditions,
+                post_states,
+            ));
+        }
+        calls
+    };
 
-        let multisig_post = multisig_account.account.clone();
-        let executor_post = executor_account.account.clone();
+    (updated_post_states(accounts, &state, &proposal), chained_calls)
+}
 
-        let mut post_states = vec![
-            AccountPostState::new(multisig_post),
-            AccountPostState::new(executor_post),
-            AccountPostState::new(proposal_post),
-        ];
+/// Execute a `MigrateToProgram` proposal: mark the multisig migrated, recreate
+/// it under the new program ID, then run the approved vault handover calls with
+/// the old vault's PDA authority.
+///
+/// Target accounts: [new_state_pda, ...handover_calls[0].account_ids, ...]
+fn execute_migration(
+    accounts: &[AccountWithMetadata],
+    target_accounts: &[AccountWithMetadata],
+    mut state: MultisigState,
+    proposal: &Proposal,
+    new_program_id: &ProgramId,
+    handover_calls: &[HandoverCall],
+) -> (Vec<AccountPostState>, Vec<ChainedCall>) {
+    assert!(state.migrated_to.is_none(), "Multisig has already been migrated");
+    state.migrated_to = Some(*new_program_id);
+    let post_states = updated_post_states(accounts, &state, proposal);
 
-        for target in target_accounts {
-            post_states.push(AccountPostState::new(target.account.clone()));
+    // ImportMultisig reads the migrated state from our state PDA, authorized
+    // by its seed so that only this program can vouch for it
+    let mut old_state = accounts[0].clone();
+    old_state.account = post_states[0].account().clone();
+    old_state.is_authorized = true;
+    let mut chained_calls = vec![ChainedCall {
+        program_id: *new_program_id,
+        instruction_data: risc0_zkvm::serde::to_vec(&Instruction::ImportMultisig).unwrap(),
+        pre_states: vec![target_accounts[0].clone(), old_state],
+        pda_seeds: vec![multisig_state_pda_seed(&state.create_key)],
+    }];
+
+    let vault_seed = PdaSeed::new(vault_pda_seed_bytes(&state.create_key));
+    let mut offset = 1;
+    for call in handover_calls {
+        let call_accounts = &target_accounts[offset..offset + call.account_ids.len()];
+        offset += call.account_ids.len();
+
+        chained_calls.push(ChainedCall {
+            program_id: call.program_id,
+            instruction_data: call.instruction_data.clone(),
+            pre_states: authorize(call_accounts, &call.authorized_indices),
+            pda_seeds: vec![vault_seed],
+        });
+    }
+
+    (post_states, chained_calls)
+}
+
+/// Follow-up call that checks `conditions` once the proposal's ChainedCall has
+/// run: values read from the target accounts now are compared against
+/// `post_states`, the executor's account states after the call, which the
+/// runtime holds to the actual ones.
+fn post_condition_call(
+    program_id: ProgramId,
+    target_accounts: &[AccountWithMetadata],
+    conditions: &[PostCondition],
+    post_states: &[ExpectedAccount],
+) -> ChainedCall {
+    let indices = post_condition_account_indices(conditions);
+    assert_eq!(
+        post_states.len(),
+        indices.len(),
+        "Expected the post-call state of {} target account(s) for the post-conditions, got {}",
+        indices.len(),
+        post_states.len()
+    );
+
+    let snapshots = conditions
+        .iter()
+        .map(|c| c.snapshot(&target_accounts[c.account_index() as usize].account))
+        .collect();
+    let pre_states = indices
+        .iter()
+        .zip(post_states)
+        .map(|(index, expected)| AccountWithMetadata {
+            account_id: target_accounts[*index as usize].account_id,
+            account: expected.to_account(),
+            is_authorized: false,
+        })
+        .collect();
+    let instruction = Instruction::AssertPostConditions { conditions: conditions.to_vec(), snapshots };
+    ChainedCall {
+        program_id,
+        instruction_data: risc0_zkvm::serde::to_vec(&instruction).unwrap(),
+        pre_states,
+        pda_seeds: vec![],
+    }
+}
+
+/// Whether a recurring proposal's next run is due by the clock account in
+/// `accounts` (laid out as for Execute). Non-recurring proposals, first runs
+/// and malformed account lists count as due, leaving the errors to `handle`.
+pub fn is_due(accounts: &[AccountWithMetadata], proposal: &Proposal) -> bool {
+    let (Some(recurrence), Some(next_at)) = (&proposal.recurrence, proposal.next_run_at()) else {
+        return true;
+    };
+    let clock_position = 3 + usize::from(proposal.depends_on.is_some());
+    match accounts.get(clock_position) {
+        Some(clock) if clock.account_id.value() == &recurrence.clock_account_id => {
+            let clock_data: Vec<u8> = clock.account.data.clone().into();
+            clock_timestamp(&clock_data).is_none_or(|now| now >= next_at)
         }
+        _ => true,
+    }
+}
+
+/// Whether the proposal `proposal` depends on has been executed, by the
+/// dependency account in `accounts` (laid out as for Execute). Proposals
+/// without a dependency and malformed account lists count as executed,
+/// leaving the errors to `handle`.
+pub fn dependency_executed(accounts: &[AccountWithMetadata], proposal: &Proposal) -> bool {
+    let (Some(dependency_index), Some(proposal_account), Some(dependency_account)) =
+        (proposal.depends_on, accounts.get(2), accounts.get(3))
+    else {
+        return true;
+    };
+    let program_id = proposal_account.account.program_owner;
+    let expected_id = compute_proposal_pda(&program_id, &proposal.multisig_create_key, dependency_index);
+    if dependency_account.account_id != expected_id || dependency_account.account.program_owner != program_id {
+        return true;
+    }
+    let dependency_data: Vec<u8> = dependency_account.account.data.clone().into();
+    match Proposal::from_bytes(&dependency_data) {
+        Ok(dependency) => dependency.status == ProposalStatus::Executed,
+        Err(_) => true,
+    }
+}
+
+/// Require `dependency_account` to be this multisig's proposal #`dependency_index`, already Executed.
+fn check_dependency(
+    program_id: &ProgramId,
+    dependency_account: &AccountWithMetadata,
+    state: &MultisigState,
+    dependency_index: u64,
+) {
+    let expected_id = compute_proposal_pda(program_id, &state.create_key, dependency_index);
+    assert_eq!(
+        dependency_account.account_id.value(),
+        expected_id.value(),
+        "Dependency account is not proposal #{}",
+        dependency_index
+    );
+    assert_eq!(
+        &dependency_account.account.program_owner, program_id,
+        "Dependency proposal is not owned by this program"
+    );
+
+    let dependency_data: Vec<u8> = dependency_account.account.data.clone().into();
+    let dependency = Proposal::from_bytes(&dependency_data)
+        .expect("Failed to deserialize dependency proposal");
+    assert_eq!(
+        dependency.status,
+        ProposalStatus::Executed,
+        "Dependency proposal #{} has not been executed",
+        dependency_index
+    );
+}
+
+/// Clone target accounts for a ChainedCall, setting `is_authorized` on the given indices.
+fn authorize(accounts: &[AccountWithMetadata], authorized_indices: &[u8]) -> Vec<AccountWithMetadata> {
+    accounts
+        .iter()
+        .enumerate()
+        .map(|(i, acc)| {
+            let mut acc = acc.clone();
+            if authorized_indices.contains(&(i as u8)) {
+                acc.is_authorized = true;
+            }
+            acc
+        })
+        .collect()
+}
 
-        (post_states, vec![chained_call])
+/// Post-states for Execute: updated state and proposal, everything else unchanged.
+fn updated_post_states(
+    accounts: &[AccountWithMetadata],
+    state: &MultisigState,
+    proposal: &Proposal,
+) -> Vec<AccountPostState> {
+    let mut multisig_post = accounts[0].account.clone();
+    multisig_post.data = state.to_bytes().try_into().unwrap();
+
+    let mut proposal_post = accounts[2].account.clone();
+    proposal_post.data = proposal.to_bytes().try_into().unwrap();
+
+    let mut post_states = vec![
+        AccountPostState::new(multisig_post),
+        AccountPostState::new(accounts[1].account.clone()),
+        AccountPostState::new(proposal_post),
+    ];
+
+    for target in &accounts[3..] {
+        post_states.push(AccountPostState::new(target.account.clone()));
     }
+
+    post_states
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use nssa_core::account::{Account, AccountId};
-    use nssa_core::program::ProgramId;
-    use multisig_core::{MultisigState, Proposal, ProposalStatus};
-
-    fn make_account(id: &[u8; 32], data: Vec<u8>, authorized: bool) -> AccountWithMetadata {
-        let mut account = Account::default();
-        account.data = data.try_into().unwrap();
-        AccountWithMetadata {
-            account_id: AccountId::new(*id),
-            account,
-            is_authorized: authorized,
-        }
-    }
+    use crate::test_utils::*;
+    use nssa_core::account::AccountId;
+    use multisig_core::{Expiry, MultisigState, Proposal, ProposalStatus, Recurrence};
 
-    fn make_state(threshold: u8, members: Vec<[u8; 32]>) -> Vec<u8> {
-        borsh::to_vec(&MultisigState::new([0u8; 32], threshold, members)).unwrap()
+    fn make_permissionless_state(threshold: u8, members: Vec<[u8; 32]>) -> Vec<u8> {
+        let mut state = MultisigState::new([0u8; 32], threshold, members);
+        state.permissionless_execution = true;
+        state.to_bytes()
     }
 
-    fn make_proposal_with_approvals(approvals: Vec<[u8; 32]>, target_account_count: u8) -> Vec<u8> {
-        let fake_program_id: ProgramId = [42u32; 8];
-        let mut proposal = Proposal::new(
-            1,
-            approvals[0],
-            [0u8; 32],
-            fake_program_id,
-            vec![0u32],
-            target_account_count,
-            vec![],
-            vec![0u8], // first target account is authorized
-        );
+    fn make_proposal_with_approvals(approvals: Vec<[u8; 32]>, target_account_ids: Vec<[u8; 32]>) -> Vec<u8> {
+        let mut proposal = new_proposal(approvals[0]);
+        proposal.target_account_ids = target_account_ids;
         for approver in &approvals[1..] {
             proposal.approve(*approver);
         }
-        borsh::to_vec(&proposal).unwrap()
+        proposal.to_bytes()
     }
 
     #[test]
@@ -207,22 +423,22 @@ mod tests {
         let members = vec![[1u8; 32], [2u8; 32], [3u8; 32]];
         let state_data = make_state(2, members);
         // 2 approvals (member 1 auto, member 2 added)
-        let proposal_data = make_proposal_with_approvals(vec![[1u8; 32], [2u8; 32]], 1);
+        let proposal_data = make_proposal_with_approvals(vec![[1u8; 32], [2u8; 32]], vec![[30u8; 32]]);
 
         let accounts = vec![
-            make_account(&[10u8; 32], state_data, false),   // multisig state
+            make_account(&state_id(), state_data, false),   // multisig state
             make_account(&[1u8; 32], vec![], true),           // executor (member)
-            make_account(&[20u8; 32], proposal_data, false),  // proposal PDA
+            make_account(&proposal_id(1), proposal_data, false),  // proposal PDA
             make_account(&[30u8; 32], vec![], false),          // target account
         ];
 
-        let (post_states, chained) = handle(&accounts, 1);
+        let (post_states, chained) = handle(&accounts, 1, &[]);
 
         assert_eq!(chained.len(), 1);
         assert_eq!(post_states.len(), 4);
 
         // Proposal should be marked executed
-        let proposal: Proposal = borsh::from_slice(
+        let proposal = Proposal::from_bytes(
             &Vec::from(post_states[2].account().data.clone())
         ).unwrap();
         assert_eq!(proposal.status, ProposalStatus::Executed);
@@ -232,22 +448,92 @@ mod tests {
         assert!(chained[0].pre_states[0].is_authorized);
     }
 
+    /// 1-of-1 proposal paying out of a vault holding 1_000, capped at a decrease of 100.
+    fn make_capped_accounts() -> (Vec<AccountWithMetadata>, PostCondition) {
+        let mut proposal = Proposal::from_bytes(
+            &make_proposal_with_approvals(vec![[1u8; 32]], vec![[30u8; 32]])
+        ).unwrap();
+        let condition = PostCondition::MaxBalanceDecrease { account_index: 0, max_decrease: 100 };
+        proposal.post_conditions = vec![condition.clone()];
+
+        let mut vault = make_account(&[30u8; 32], vec![], false);
+        vault.account.balance = 1_000;
+        let accounts = vec![
+            make_account(&state_id(), make_state(1, vec![[1u8; 32]]), false),
+            make_account(&[1u8; 32], vec![], true),
+            make_account(&proposal_id(1), proposal.to_bytes(), false),
+            vault,
+        ];
+        (accounts, condition)
+    }
+
+    /// The vault as the executor expects it after the proposal's call.
+    fn vault_after(accounts: &[AccountWithMetadata], balance: u128) -> ExpectedAccount {
+        let mut vault = accounts[3].account.clone();
+        vault.balance = balance;
+        ExpectedAccount::from(&vault)
+    }
+
+    #[test]
+    fn test_execute_with_post_conditions_emits_check_call() {
+        let (accounts, condition) = make_capped_accounts();
+
+        let (_, chained) = handle(&accounts, 1, &[vault_after(&accounts, 950)]);
+
+        assert_eq!(chained.len(), 2);
+        let check = &chained[1];
+        assert_eq!(check.program_id, accounts[2].account.program_owner);
+        let expected = Instruction::AssertPostConditions { conditions: vec![condition], snapshots: vec![1_000] };
+        assert_eq!(check.instruction_data, risc0_zkvm::serde::to_vec(&expected).unwrap());
+        // The check reads the vault as left by the call, not as it was before
+        assert_eq!(check.pre_states.len(), 1);
+        assert_eq!(check.pre_states[0].account_id, accounts[3].account_id);
+        assert_eq!(check.pre_states[0].account.balance, 950);
+        assert!(!check.pre_states[0].is_authorized);
+
+        crate::assert_post_conditions::handle(&check.pre_states, &post_conditions_of(&accounts), &[1_000]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Target account 0 decreased by 500, more than the approved 100")]
+    fn test_post_conditions_fail_when_target_overspends() {
+        let (accounts, _) = make_capped_accounts();
+
+        // The target takes 500. The runtime only runs the check against the
+        // vault as it actually is, so the executor must report the overspend.
+        let (_, chained) = handle(&accounts, 1, &[vault_after(&accounts, 500)]);
+
+        crate::assert_post_conditions::handle(&chained[1].pre_states, &post_conditions_of(&accounts), &[1_000]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Expected the post-call state of 1 target account(s)")]
+    fn test_execute_with_post_conditions_requires_post_states() {
+        let (accounts, _) = make_capped_accounts();
+        handle(&accounts, 1, &[]);
+    }
+
+    fn post_conditions_of(accounts: &[AccountWithMetadata]) -> Vec<PostCondition> {
+        let data: Vec<u8> = accounts[2].account.data.clone().into();
+        Proposal::from_bytes(&data).unwrap().post_conditions
+    }
+
     #[test]
     #[should_panic(expected = "enough approvals")]
     fn test_execute_below_threshold_fails() {
         let members = vec![[1u8; 32], [2u8; 32], [3u8; 32]];
         let state_data = make_state(2, members);
         // Only 1 approval (proposer only)
-        let proposal_data = make_proposal_with_approvals(vec![[1u8; 32]], 1);
+        let proposal_data = make_proposal_with_approvals(vec![[1u8; 32]], vec![[30u8; 32]]);
 
         let accounts = vec![
-            make_account(&[10u8; 32], state_data, false),
+            make_account(&state_id(), state_data, false),
             make_account(&[1u8; 32], vec![], true),
-            make_account(&[20u8; 32], proposal_data, false),
+            make_account(&proposal_id(1), proposal_data, false),
             make_account(&[30u8; 32], vec![], false),
         ];
 
-        handle(&accounts, 1);
+        handle(&accounts, 1, &[]);
     }
 
     #[test]
@@ -255,17 +541,17 @@ mod tests {
     fn test_execute_wrong_account_count_fails() {
         let members = vec![[1u8; 32], [2u8; 32]];
         let state_data = make_state(2, members);
-        let proposal_data = make_proposal_with_approvals(vec![[1u8; 32], [2u8; 32]], 1);
+        let proposal_data = make_proposal_with_approvals(vec![[1u8; 32], [2u8; 32]], vec![[30u8; 32]]);
 
         // Missing the target account
         let accounts = vec![
-            make_account(&[10u8; 32], state_data, false),
+            make_account(&state_id(), state_data, false),
             make_account(&[1u8; 32], vec![], true),
-            make_account(&[20u8; 32], proposal_data, false),
+            make_account(&proposal_id(1), proposal_data, false),
             // no target account!
         ];
 
-        handle(&accounts, 1);
+        handle(&accounts, 1, &[]);
     }
 
     #[test]
@@ -273,16 +559,283 @@ mod tests {
     fn test_execute_non_member_fails() {
         let members = vec![[1u8; 32], [2u8; 32]];
         let state_data = make_state(2, members);
-        let proposal_data = make_proposal_with_approvals(vec![[1u8; 32], [2u8; 32]], 1);
+        let proposal_data = make_proposal_with_approvals(vec![[1u8; 32], [2u8; 32]], vec![[30u8; 32]]);
 
         let accounts = vec![
-            make_account(&[10u8; 32], state_data, false),
+            make_account(&state_id(), state_data, false),
             make_account(&[99u8; 32], vec![], true), // NOT a member
+            make_account(&proposal_id(1), proposal_data, false),
+            make_account(&[30u8; 32], vec![], false),
+        ];
+
+        handle(&accounts, 1, &[]);
+    }
+
+    #[test]
+    #[should_panic(expected = "need 2, have 1")]
+    fn test_execute_ignores_approvals_of_removed_members() {
+        // Member 3 approved and was removed afterwards
+        let state_data = make_state(2, vec![[1u8; 32], [2u8; 32]]);
+        let proposal_data = make_proposal_with_approvals(vec![[1u8; 32], [3u8; 32]], vec![[30u8; 32]]);
+
+        let accounts = vec![
+            make_account(&state_id(), state_data, false),
+            make_account(&[1u8; 32], vec![], true),
+            make_account(&proposal_id(1), proposal_data, false),
+            make_account(&[30u8; 32], vec![], false),
+        ];
+
+        handle(&accounts, 1, &[]);
+    }
+
+    /// Proposal #2 (depending on #1) and an account holding proposal #1 with the given status.
+    fn make_dependent_accounts(dependency_status: ProposalStatus) -> Vec<AccountWithMetadata> {
+        let mut dependent = Proposal::from_bytes(
+            &make_proposal_with_approvals(vec![[1u8; 32], [2u8; 32]], vec![[30u8; 32]])
+        ).unwrap();
+        dependent.index = 2;
+        dependent.depends_on = Some(1);
+
+        let mut dependency = Proposal::from_bytes(
+            &make_proposal_with_approvals(vec![[1u8; 32], [2u8; 32]], vec![[31u8; 32]])
+        ).unwrap();
+        dependency.status = dependency_status;
+
+        vec![
+            make_account(&state_id(), make_state(2, vec![[1u8; 32], [2u8; 32]]), false),
+            make_account(&[1u8; 32], vec![], true),
+            make_account(&proposal_id(2), dependent.to_bytes(), false),
+            make_account(&proposal_id(1), dependency.to_bytes(), false),
+            make_account(&[30u8; 32], vec![], false),
+        ]
+    }
+
+    #[test]
+    fn test_execute_after_dependency_executed() {
+        let accounts = make_dependent_accounts(ProposalStatus::Executed);
+
+        let (post_states, chained) = handle(&accounts, 2, &[]);
+
+        assert_eq!(post_states.len(), 5);
+        assert_eq!(chained.len(), 1);
+        assert_eq!(chained[0].pre_states.len(), 1);
+        assert_eq!(chained[0].pre_states[0].account_id, accounts[4].account_id);
+    }
+
+    #[test]
+    #[should_panic(expected = "Dependency proposal #1 has not been executed")]
+    fn test_execute_before_dependency_fails() {
+        handle(&make_dependent_accounts(ProposalStatus::Active), 2, &[]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Dependency account is not proposal #1")]
+    fn test_execute_with_wrong_dependency_account_fails() {
+        let mut accounts = make_dependent_accounts(ProposalStatus::Executed);
+        accounts[3].account_id = AccountId::new([21u8; 32]);
+        handle(&accounts, 2, &[]);
+    }
+
+    /// A 2-of-2-approved monthly proposal with the given run history, plus a clock at `now`.
+    fn make_recurring_accounts(runs_executed: u32, last_executed_at: Option<u64>, now: u64) -> Vec<AccountWithMetadata> {
+        let mut proposal = Proposal::from_bytes(
+            &make_proposal_with_approvals(vec![[1u8; 32], [2u8; 32]], vec![[30u8; 32]])
+        ).unwrap();
+        proposal.recurrence = Some(Recurrence { clock_account_id: [50u8; 32], interval: 2_592_000, max_runs: 3 });
+        proposal.runs_executed = runs_executed;
+        proposal.last_executed_at = last_executed_at;
+
+        vec![
+            make_account(&state_id(), make_state(2, vec![[1u8; 32], [2u8; 32]]), false),
+            make_account(&[1u8; 32], vec![], true),
+            make_account(&proposal_id(1), proposal.to_bytes(), false),
+            make_account(&[50u8; 32], now.to_le_bytes().to_vec(), false),
+            make_account(&[30u8; 32], vec![], false),
+        ]
+    }
+
+    #[test]
+    fn test_execute_recurring_run_keeps_proposal_active() {
+        let (post_states, chained) = handle(&make_recurring_accounts(0, None, 1_000), 1, &[]);
+
+        assert_eq!(chained.len(), 1);
+        let proposal = Proposal::from_bytes(&Vec::from(post_states[2].account().data.clone())).unwrap();
+        assert_eq!(proposal.status, ProposalStatus::Active);
+        assert_eq!(proposal.runs_executed, 1);
+        assert_eq!(proposal.last_executed_at, Some(1_000));
+    }
+
+    #[test]
+    fn test_execute_recurring_last_run_marks_executed() {
+        let (post_states, _) = handle(&make_recurring_accounts(2, Some(1_000), 2_593_000), 1, &[]);
+
+        let proposal = Proposal::from_bytes(&Vec::from(post_states[2].account().data.clone())).unwrap();
+        assert_eq!(proposal.status, ProposalStatus::Executed);
+        assert_eq!(proposal.remaining_runs(), Some(0));
+    }
+
+    #[test]
+    #[should_panic(expected = "Next run is not due until 2593000")]
+    fn test_execute_recurring_before_interval_fails() {
+        handle(&make_recurring_accounts(1, Some(1_000), 2_592_999), 1, &[]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Clock account does not match")]
+    fn test_execute_recurring_with_wrong_clock_fails() {
+        let mut accounts = make_recurring_accounts(0, None, 1_000);
+        accounts[3].account_id = AccountId::new([51u8; 32]);
+        handle(&accounts, 1, &[]);
+    }
+
+    /// A 2-of-2-approved proposal expiring at 1000, plus a clock at `now`.
+    fn make_expiring_accounts(now: u64) -> Vec<AccountWithMetadata> {
+        let mut proposal = Proposal::from_bytes(
+            &make_proposal_with_approvals(vec![[1u8; 32], [2u8; 32]], vec![[30u8; 32]])
+        ).unwrap();
+        proposal.expiry = Some(Expiry { clock_account_id: [50u8; 32], expires_at: 1_000 });
+
+        vec![
+            make_account(&state_id(), make_state(2, vec![[1u8; 32], [2u8; 32]]), false),
+            make_account(&[1u8; 32], vec![], true),
+            make_account(&proposal_id(1), proposal.to_bytes(), false),
+            make_account(&[50u8; 32], now.to_le_bytes().to_vec(), false),
+            make_account(&[30u8; 32], vec![], false),
+        ]
+    }
+
+    #[test]
+    fn test_execute_before_expiry() {
+        let (post_states, chained) = handle(&make_expiring_accounts(999), 1, &[]);
+
+        assert_eq!(chained.len(), 1);
+        let proposal = Proposal::from_bytes(&Vec::from(post_states[2].account().data.clone())).unwrap();
+        assert_eq!(proposal.status, ProposalStatus::Executed);
+    }
+
+    #[test]
+    #[should_panic(expected = "Proposal expired at 1000")]
+    fn test_execute_after_expiry_fails() {
+        handle(&make_expiring_accounts(1_000), 1, &[]);
+    }
+
+    #[test]
+    #[should_panic(expected = "not owned by this program")]
+    fn test_execute_with_foreign_state_account_fails() {
+        let members = vec![[1u8; 32], [2u8; 32]];
+        let mut state_account = make_account(&state_id(), make_state(2, members), false);
+        state_account.account.program_owner = [66u32; 8];
+        let proposal_data = make_proposal_with_approvals(vec![[1u8; 32], [2u8; 32]], vec![[30u8; 32]]);
+
+        let accounts = vec![
+            state_account,
+            make_account(&[1u8; 32], vec![], true),
+            make_account(&proposal_id(1), proposal_data, false),
+            make_account(&[30u8; 32], vec![], false),
+        ];
+
+        handle(&accounts, 1, &[]);
+    }
+
+    #[test]
+    #[should_panic(expected = "not this multisig's state PDA")]
+    fn test_execute_with_state_account_at_wrong_address_fails() {
+        let proposal_data = make_proposal_with_approvals(vec![[1u8; 32], [2u8; 32]], vec![[30u8; 32]]);
+
+        let accounts = vec![
+            make_account(&[10u8; 32], make_state(2, vec![[1u8; 32], [2u8; 32]]), false),
+            make_account(&[1u8; 32], vec![], true),
+            make_account(&proposal_id(1), proposal_data, false),
+            make_account(&[30u8; 32], vec![], false),
+        ];
+
+        handle(&accounts, 1, &[]);
+    }
+
+    #[test]
+    #[should_panic(expected = "not proposal #1's PDA")]
+    fn test_execute_with_proposal_at_wrong_address_fails() {
+        let proposal_data = make_proposal_with_approvals(vec![[1u8; 32], [2u8; 32]], vec![[30u8; 32]]);
+
+        let accounts = vec![
+            make_account(&state_id(), make_state(2, vec![[1u8; 32], [2u8; 32]]), false),
+            make_account(&[1u8; 32], vec![], true),
             make_account(&[20u8; 32], proposal_data, false),
             make_account(&[30u8; 32], vec![], false),
         ];
 
-        handle(&accounts, 1);
+        handle(&accounts, 1, &[]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Failed to deserialize multisig state")]
+    fn test_execute_with_proposal_as_state_fails() {
+        let proposal_data = make_proposal_with_approvals(vec![[1u8; 32], [2u8; 32]], vec![[30u8; 32]]);
+
+        let accounts = vec![
+            make_account(&state_id(), proposal_data.clone(), false),
+            make_account(&[1u8; 32], vec![], true),
+            make_account(&proposal_id(1), proposal_data, false),
+            make_account(&[30u8; 32], vec![], false),
+        ];
+
+        handle(&accounts, 1, &[]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Target account 0 does not match")]
+    fn test_execute_swapped_target_account_fails() {
+        let members = vec![[1u8; 32], [2u8; 32]];
+        let state_data = make_state(2, members);
+        let proposal_data = make_proposal_with_approvals(vec![[1u8; 32], [2u8; 32]], vec![[30u8; 32]]);
+
+        let accounts = vec![
+            make_account(&state_id(), state_data, false),
+            make_account(&[1u8; 32], vec![], true),
+            make_account(&proposal_id(1), proposal_data, false),
+            make_account(&[66u8; 32], vec![], false), // not the approved recipient
+        ];
+
+        handle(&accounts, 1, &[]);
+    }
+
+    #[test]
+    fn test_execute_permissionless_by_non_member() {
+        let members = vec![[1u8; 32], [2u8; 32]];
+        let state_data = make_permissionless_state(2, members);
+        let proposal_data = make_proposal_with_approvals(vec![[1u8; 32], [2u8; 32]], vec![[30u8; 32]]);
+
+        let accounts = vec![
+            make_account(&state_id(), state_data, false),
+            make_account(&[99u8; 32], vec![], true), // relayer, not a member
+            make_account(&proposal_id(1), proposal_data, false),
+            make_account(&[30u8; 32], vec![], false),
+        ];
+
+        let (post_states, chained) = handle(&accounts, 1, &[]);
+
+        assert_eq!(chained.len(), 1);
+        let proposal = Proposal::from_bytes(
+            &Vec::from(post_states[2].account().data.clone())
+        ).unwrap();
+        assert_eq!(proposal.status, ProposalStatus::Executed);
+    }
+
+    #[test]
+    #[should_panic(expected = "enough approvals")]
+    fn test_execute_permissionless_still_requires_threshold() {
+        let members = vec![[1u8; 32], [2u8; 32]];
+        let state_data = make_permissionless_state(2, members);
+        let proposal_data = make_proposal_with_approvals(vec![[1u8; 32]], vec![[30u8; 32]]);
+
+        let accounts = vec![
+            make_account(&state_id(), state_data, false),
+            make_account(&[99u8; 32], vec![], true),
+            make_account(&proposal_id(1), proposal_data, false),
+            make_account(&[30u8; 32], vec![], false),
+        ];
+
+        handle(&accounts, 1, &[]);
     }
 
     // -- Config action tests --
@@ -293,11 +846,13 @@ mod tests {
             approvals[0],
             [0u8; 32],
             action,
+            String::new(),
+            None,
         );
         for approver in &approvals[1..] {
             proposal.approve(*approver);
         }
-        borsh::to_vec(&proposal).unwrap()
+        proposal.to_bytes()
     }
 
     #[test]
@@ -310,15 +865,15 @@ mod tests {
         );
 
         let accounts = vec![
-            make_account(&[10u8; 32], state_data, false),
+            make_account(&state_id(), state_data, false),
             make_account(&[1u8; 32], vec![], true),
-            make_account(&[20u8; 32], proposal_data, false),
+            make_account(&proposal_id(1), proposal_data, false),
         ];
 
-        let (post_states, chained) = handle(&accounts, 1);
+        let (post_states, chained) = handle(&accounts, 1, &[]);
 
         assert!(chained.is_empty());
-        let state: MultisigState = borsh::from_slice(
+        let state = MultisigState::from_bytes(
             &Vec::from(post_states[0].account().data.clone())
         ).unwrap();
         assert_eq!(state.member_count, 4);
@@ -335,15 +890,15 @@ mod tests {
         );
 
         let accounts = vec![
-            make_account(&[10u8; 32], state_data, false),
+            make_account(&state_id(), state_data, false),
             make_account(&[1u8; 32], vec![], true),
-            make_account(&[20u8; 32], proposal_data, false),
+            make_account(&proposal_id(1), proposal_data, false),
         ];
 
-        let (post_states, chained) = handle(&accounts, 1);
+        let (post_states, chained) = handle(&accounts, 1, &[]);
 
         assert!(chained.is_empty());
-        let state: MultisigState = borsh::from_slice(
+        let state = MultisigState::from_bytes(
             &Vec::from(post_states[0].account().data.clone())
         ).unwrap();
         assert_eq!(state.member_count, 2);
@@ -361,12 +916,31 @@ mod tests {
         );
 
         let accounts = vec![
-            make_account(&[10u8; 32], state_data, false),
+            make_account(&state_id(), state_data, false),
             make_account(&[1u8; 32], vec![], true),
-            make_account(&[20u8; 32], proposal_data, false),
+            make_account(&proposal_id(1), proposal_data, false),
         ];
 
-        handle(&accounts, 1);
+        handle(&accounts, 1, &[]);
+    }
+
+    #[test]
+    #[should_panic(expected = "less than reject threshold (3)")]
+    fn test_execute_remove_member_would_break_reject_threshold() {
+        let mut state = MultisigState::new([0u8; 32], 2, vec![[1u8; 32], [2u8; 32], [3u8; 32]]);
+        state.reject_threshold = Some(3);
+        let proposal_data = make_config_proposal(
+            vec![[1u8; 32], [2u8; 32]],
+            ConfigAction::RemoveMember { member: [3u8; 32] },
+        );
+
+        let accounts = vec![
+            make_account(&state_id(), state.to_bytes(), false),
+            make_account(&[1u8; 32], vec![], true),
+            make_account(&proposal_id(1), proposal_data, false),
+        ];
+
+        handle(&accounts, 1, &[]);
     }
 
     #[test]
@@ -379,15 +953,15 @@ mod tests {
         );
 
         let accounts = vec![
-            make_account(&[10u8; 32], state_data, false),
+            make_account(&state_id(), state_data, false),
             make_account(&[1u8; 32], vec![], true),
-            make_account(&[20u8; 32], proposal_data, false),
+            make_account(&proposal_id(1), proposal_data, false),
         ];
 
-        let (post_states, chained) = handle(&accounts, 1);
+        let (post_states, chained) = handle(&accounts, 1, &[]);
 
         assert!(chained.is_empty());
-        let state: MultisigState = borsh::from_slice(
+        let state = MultisigState::from_bytes(
             &Vec::from(post_states[0].account().data.clone())
         ).unwrap();
         assert_eq!(state.threshold, 3);
@@ -404,11 +978,186 @@ mod tests {
         );
 
         let accounts = vec![
-            make_account(&[10u8; 32], state_data, false),
+            make_account(&state_id(), state_data, false),
             make_account(&[1u8; 32], vec![], true),
-            make_account(&[20u8; 32], proposal_data, false),
+            make_account(&proposal_id(1), proposal_data, false),
+        ];
+
+        handle(&accounts, 1, &[]);
+    }
+
+    #[test]
+    fn test_execute_set_permissionless_execution() {
+        let members = vec![[1u8; 32], [2u8; 32], [3u8; 32]];
+        let state_data = make_state(2, members);
+        let proposal_data = make_config_proposal(
+            vec![[1u8; 32], [2u8; 32]],
+            ConfigAction::SetPermissionlessExecution { enabled: true },
+        );
+
+        let accounts = vec![
+            make_account(&state_id(), state_data, false),
+            make_account(&[1u8; 32], vec![], true),
+            make_account(&proposal_id(1), proposal_data, false),
+        ];
+
+        let (post_states, chained) = handle(&accounts, 1, &[]);
+
+        assert!(chained.is_empty());
+        let state = MultisigState::from_bytes(
+            &Vec::from(post_states[0].account().data.clone())
+        ).unwrap();
+        assert!(state.permissionless_execution);
+    }
+
+    #[test]
+    fn test_execute_set_reject_threshold() {
+        let state_data = make_state(2, vec![[1u8; 32], [2u8; 32], [3u8; 32]]);
+        let proposal_data = make_config_proposal(
+            vec![[1u8; 32], [2u8; 32]],
+            ConfigAction::SetRejectThreshold { reject_threshold: Some(1) },
+        );
+
+        let accounts = vec![
+            make_account(&state_id(), state_data, false),
+            make_account(&[1u8; 32], vec![], true),
+            make_account(&proposal_id(1), proposal_data, false),
+        ];
+
+        let (post_states, _) = handle(&accounts, 1, &[]);
+
+        let state = MultisigState::from_bytes(
+            &Vec::from(post_states[0].account().data.clone())
+        ).unwrap();
+        assert_eq!(state.reject_threshold, Some(1));
+    }
+
+    #[test]
+    #[should_panic(expected = "Reject threshold (4) cannot exceed member count (3)")]
+    fn test_execute_set_reject_threshold_too_high() {
+        let state_data = make_state(2, vec![[1u8; 32], [2u8; 32], [3u8; 32]]);
+        let proposal_data = make_config_proposal(
+            vec![[1u8; 32], [2u8; 32]],
+            ConfigAction::SetRejectThreshold { reject_threshold: Some(4) },
+        );
+
+        let accounts = vec![
+            make_account(&state_id(), state_data, false),
+            make_account(&[1u8; 32], vec![], true),
+            make_account(&proposal_id(1), proposal_data, false),
+        ];
+
+        handle(&accounts, 1, &[]);
+    }
+
+    fn make_migration_action() -> ConfigAction {
+        ConfigAction::MigrateToProgram {
+            new_program_id: [9u32; 8],
+            handover_calls: vec![HandoverCall {
+                program_id: [5u32; 8],
+                instruction_data: vec![1u32],
+                account_ids: vec![[40u8; 32], [41u8; 32]], // old vault holding, new vault holding
+                authorized_indices: vec![0],
+            }],
+        }
+    }
+
+    #[test]
+    fn test_execute_migrate_to_program() {
+        let members = vec![[1u8; 32], [2u8; 32], [3u8; 32]];
+        let state_data = make_state(2, members);
+        let proposal_data = make_config_proposal(vec![[1u8; 32], [2u8; 32]], make_migration_action());
+        let new_state_pda = *multisig_core::compute_multisig_state_pda(&[9u32; 8], &[0u8; 32]).value();
+
+        let accounts = vec![
+            make_account(&state_id(), state_data, false),
+            make_account(&[1u8; 32], vec![], true),
+            make_account(&proposal_id(1), proposal_data, false),
+            make_account(&new_state_pda, vec![], false),
+            make_account(&[40u8; 32], vec![], false),
+            make_account(&[41u8; 32], vec![], false),
+        ];
+
+        let (post_states, chained) = handle(&accounts, 1, &[]);
+
+        assert_eq!(post_states.len(), 6);
+        assert_eq!(chained.len(), 2);
+
+        // Import into the new program
+        assert_eq!(chained[0].program_id, [9u32; 8]);
+        assert_eq!(chained[0].pre_states.len(), 2);
+        assert_eq!(chained[0].pre_states[0].account_id.value(), &new_state_pda);
+        assert!(chained[0].pre_states[1].is_authorized);
+        assert_eq!(chained[0].pda_seeds.len(), 1);
+        let old_state = MultisigState::from_bytes(
+            &Vec::from(chained[0].pre_states[1].account.data.clone())
+        ).unwrap();
+        assert_eq!(old_state.migrated_to, Some([9u32; 8]));
+
+        // Vault handover with the old vault's PDA authority
+        assert_eq!(chained[1].program_id, [5u32; 8]);
+        assert_eq!(chained[1].pda_seeds.len(), 1);
+        assert!(chained[1].pre_states[0].is_authorized);
+        assert!(!chained[1].pre_states[1].is_authorized);
+
+        let proposal = Proposal::from_bytes(
+            &Vec::from(post_states[2].account().data.clone())
+        ).unwrap();
+        assert_eq!(proposal.status, ProposalStatus::Executed);
+    }
+
+    #[test]
+    #[should_panic(expected = "migrated to another program")]
+    fn test_execute_after_migration_fails() {
+        let mut state = MultisigState::new([0u8; 32], 2, vec![[1u8; 32], [2u8; 32]]);
+        state.migrated_to = Some([9u32; 8]);
+
+        let accounts = vec![
+            make_account(&state_id(), state.to_bytes(), false),
+            make_account(&[1u8; 32], vec![], true),
+            make_account(&proposal_id(1), make_proposal_with_approvals(vec![[1u8; 32], [2u8; 32]], vec![[30u8; 32]]), false),
+            make_account(&[30u8; 32], vec![], false),
+        ];
+
+        handle(&accounts, 1, &[]);
+    }
+
+    #[test]
+    #[should_panic(expected = "already been migrated")]
+    fn test_execute_migrate_twice_fails() {
+        let mut state = MultisigState::new([0u8; 32], 2, vec![[1u8; 32], [2u8; 32], [3u8; 32]]);
+        state.migrated_to = Some([8u32; 8]);
+        let proposal_data = make_config_proposal(vec![[1u8; 32], [2u8; 32]], make_migration_action());
+        let new_state_pda = *multisig_core::compute_multisig_state_pda(&[9u32; 8], &[0u8; 32]).value();
+
+        let accounts = vec![
+            make_account(&state_id(), state.to_bytes(), false),
+            make_account(&[1u8; 32], vec![], true),
+            make_account(&proposal_id(1), proposal_data, false),
+            make_account(&new_state_pda, vec![], false),
+            make_account(&[40u8; 32], vec![], false),
+            make_account(&[41u8; 32], vec![], false),
+        ];
+
+        handle(&accounts, 1, &[]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Target account 0 does not match")]
+    fn test_execute_migrate_wrong_new_state_account_fails() {
+        let members = vec![[1u8; 32], [2u8; 32], [3u8; 32]];
+        let state_data = make_state(2, members);
+        let proposal_data = make_config_proposal(vec![[1u8; 32], [2u8; 32]], make_migration_action());
+
+        let accounts = vec![
+            make_account(&state_id(), state_data, false),
+            make_account(&[1u8; 32], vec![], true),
+            make_account(&proposal_id(1), proposal_data, false),
+            make_account(&[66u8; 32], vec![], false), // not the new program's state PDA
+            make_account(&[40u8; 32], vec![], false),
+            make_account(&[41u8; 32], vec![], false),
         ];
 
-        handle(&accounts, 1);
+        handle(&accounts, 1, &[]);
     }
 }
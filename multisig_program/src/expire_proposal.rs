@@ -0,0 +1,110 @@
+// ExpireProposal handler — marks a proposal Expired once its deadline has passed.
+//
+// Execute already refuses a proposal past its deadline; this records the
+// outcome so the proposal no longer shows as pending. The clock decides, so
+// anyone can send it.
+//
+// Expected accounts:
+// - accounts[0]: multisig_state PDA
+// - accounts[1]: proposal PDA account (owned by multisig program, Active or Draft)
+// - accounts[2]: the clock account named in the proposal's expiry
+
+use nssa_core::account::AccountWithMetadata;
+use nssa_core::program::{AccountPostState, ChainedCall};
+use multisig_core::{MultisigState, Proposal, ProposalEvent, clock_timestamp};
+
+pub fn handle(
+    accounts: &[AccountWithMetadata],
+    _proposal_index: u64,
+) -> (Vec<AccountPostState>, Vec<ChainedCall>) {
+    assert!(accounts.len() >= 3, "ExpireProposal requires multisig_state + proposal + clock accounts");
+
+    let multisig_account = &accounts[0];
+    let proposal_account = &accounts[1];
+    let clock_account = &accounts[2];
+
+    let state_data: Vec<u8> = multisig_account.account.data.clone().into();
+    let state = MultisigState::from_bytes(&state_data)
+        .expect("Failed to deserialize multisig state");
+
+    let proposal_data: Vec<u8> = proposal_account.account.data.clone().into();
+    let mut proposal = Proposal::from_bytes(&proposal_data)
+        .expect("Failed to deserialize proposal");
+
+    assert_eq!(proposal.multisig_create_key, state.create_key, "Proposal does not belong to this multisig");
+    crate::check_multisig_accounts(multisig_account, proposal_account, &state, &proposal);
+
+    let expiry = proposal.expiry.as_ref().expect("Proposal has no expiry");
+    assert_eq!(
+        clock_account.account_id.value(),
+        &expiry.clock_account_id,
+        "Clock account does not match the approved proposal"
+    );
+    let clock_data: Vec<u8> = clock_account.account.data.clone().into();
+    let now = clock_timestamp(&clock_data).expect("Clock account does not hold a timestamp");
+
+    if let Err(e) = proposal.apply(&state, ProposalEvent::Expire { now }) {
+        panic!("{}", e);
+    }
+
+    let mut proposal_post = proposal_account.account.clone();
+    proposal_post.data = proposal.to_bytes().try_into().unwrap();
+
+    (
+        vec![
+            AccountPostState::new(multisig_account.account.clone()),
+            AccountPostState::new(proposal_post),
+            AccountPostState::new(clock_account.account.clone()),
+        ],
+        vec![],
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+    use multisig_core::{Expiry, ProposalStatus};
+
+    const CLOCK: [u8; 32] = [50u8; 32];
+
+    fn make_accounts(now: u64) -> Vec<AccountWithMetadata> {
+        let mut proposal = new_proposal([1u8; 32]);
+        proposal.expiry = Some(Expiry { clock_account_id: CLOCK, expires_at: 1_000 });
+        vec![
+            make_account(&state_id(), make_state_with_proposal(2, vec![[1u8; 32], [2u8; 32]]), false),
+            make_account(&proposal_id(1), proposal.to_bytes(), false),
+            make_account(&CLOCK, now.to_le_bytes().to_vec(), false),
+        ]
+    }
+
+    #[test]
+    fn test_expire_after_deadline() {
+        let (post_states, _) = handle(&make_accounts(1_000), 1);
+
+        let proposal = Proposal::from_bytes(&Vec::from(post_states[1].account().data.clone())).unwrap();
+        assert_eq!(proposal.status, ProposalStatus::Expired);
+    }
+
+    #[test]
+    #[should_panic(expected = "does not expire until 1000")]
+    fn test_expire_before_deadline_fails() {
+        handle(&make_accounts(999), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "Clock account does not match")]
+    fn test_expire_with_other_clock_fails() {
+        let mut accounts = make_accounts(5_000);
+        accounts[2] = make_account(&[51u8; 32], 5_000u64.to_le_bytes().to_vec(), false);
+        handle(&accounts, 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "Proposal has no expiry")]
+    fn test_expire_without_expiry_fails() {
+        let mut accounts = make_accounts(5_000);
+        accounts[1] = make_account(&proposal_id(1), make_proposal([1u8; 32]), false);
+        handle(&accounts, 1);
+    }
+}
@@ -0,0 +1,177 @@
+// ImportMultisig handler — recreates a multisig migrated from another program ID.
+//
+// Reached via ChainedCall when a `MigrateToProgram` proposal executes in the old
+// program. The migrated state (members, threshold, counters) is copied from the
+// old state account to this program's state PDA for the same create_key.
+//
+// The old state must be authorized, which only the program owning it can do
+// (through its state PDA seed), and must be that program's state PDA for the
+// create_key. Its `migrated_to` names the program it handed over to, which
+// must be the program this state PDA is derived from. A program that forges such
+// an old state can only take an unused create_key — the same squatting exposure
+// as CreateMultisig. A migration whose target PDA was already taken fails
+// atomically, so vault handover calls never run.
+//
+// Expected accounts:
+// - accounts[0]: multisig_state PDA under this program (must be uninitialized)
+// - accounts[1]: old multisig_state PDA (authorized, marked migrated)
+
+use nssa_core::account::{Account, AccountWithMetadata};
+use nssa_core::program::{AccountPostState, ChainedCall};
+use multisig_core::{MultisigState, compute_multisig_state_pda};
+
+pub fn handle(
+    accounts: &[AccountWithMetadata],
+) -> (Vec<AccountPostState>, Vec<ChainedCall>) {
+    assert!(accounts.len() >= 2, "ImportMultisig requires the new and old multisig_state accounts");
+
+    let new_state_account = &accounts[0];
+    let old_state_account = &accounts[1];
+
+    assert!(
+        new_state_account.account == Account::default(),
+        "Multisig state account must be uninitialized"
+    );
+    assert!(old_state_account.is_authorized, "Old multisig state must be authorized by its program");
+
+    let old_state_data: Vec<u8> = old_state_account.account.data.clone().into();
+    let mut state = MultisigState::from_bytes(&old_state_data)
+        .expect("Failed to deserialize old multisig state");
+
+    assert_eq!(
+        old_state_account.account_id,
+        compute_multisig_state_pda(&old_state_account.account.program_owner, &state.create_key),
+        "Old multisig state is not the state PDA of its program"
+    );
+    let new_program_id = state.migrated_to.expect("Old multisig has not been migrated");
+    assert_eq!(
+        new_state_account.account_id,
+        compute_multisig_state_pda(&new_program_id, &state.create_key),
+        "Multisig state account is not the state PDA of the migration target"
+    );
+
+    assert_eq!(state.member_count as usize, state.members.len(), "Member count does not match member list");
+    assert!(state.threshold >= 1, "Threshold must be at least 1");
+    assert!(state.threshold <= state.member_count, "Threshold cannot exceed member count");
+    if let Some(cutoff) = state.reject_threshold {
+        assert!(cutoff >= 1, "Reject threshold must be at least 1");
+    }
+
+    state.migrated_to = None;
+    let mut multisig_account = Account::default();
+    multisig_account.data = state.to_bytes().try_into().unwrap();
+
+    (
+        vec![
+            AccountPostState::new_claimed(multisig_account),
+            AccountPostState::new(old_state_account.account.clone()),
+        ],
+        vec![],
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nssa_core::program::ProgramId;
+
+    const OLD_PROGRAM: ProgramId = [1u32; 8];
+    const NEW_PROGRAM: ProgramId = [2u32; 8];
+
+    fn make_migrated_state(state: &mut MultisigState) -> AccountWithMetadata {
+        state.migrated_to = Some(NEW_PROGRAM);
+        let mut account = Account::default();
+        account.program_owner = OLD_PROGRAM;
+        account.data = state.to_bytes().try_into().unwrap();
+        AccountWithMetadata {
+            account_id: compute_multisig_state_pda(&OLD_PROGRAM, &state.create_key),
+            account,
+            is_authorized: true,
+        }
+    }
+
+    fn make_new_state(create_key: &[u8; 32], data: Vec<u8>) -> AccountWithMetadata {
+        let mut account = Account::default();
+        account.data = data.try_into().unwrap();
+        AccountWithMetadata {
+            account_id: compute_multisig_state_pda(&NEW_PROGRAM, create_key),
+            account,
+            is_authorized: false,
+        }
+    }
+
+    #[test]
+    fn test_import_multisig_writes_state() {
+        let mut state = MultisigState::new([1u8; 32], 2, vec![[10u8; 32], [11u8; 32], [12u8; 32]]);
+        state.transaction_index = 7;
+
+        let accounts = vec![make_new_state(&state.create_key, vec![]), make_migrated_state(&mut state)];
+        let (post_states, chained) = handle(&accounts);
+
+        assert!(chained.is_empty());
+        assert_eq!(post_states.len(), 2);
+
+        let imported = MultisigState::from_bytes(
+            &Vec::from(post_states[0].account().data.clone())
+        ).unwrap();
+        assert_eq!(imported.create_key, [1u8; 32]);
+        assert_eq!(imported.members.len(), 3);
+        assert_eq!(imported.transaction_index, 7);
+        assert_eq!(imported.migrated_to, None);
+    }
+
+    #[test]
+    #[should_panic(expected = "must be uninitialized")]
+    fn test_import_multisig_existing_state_fails() {
+        let mut state = MultisigState::new([1u8; 32], 1, vec![[10u8; 32]]);
+        let accounts = vec![make_new_state(&state.create_key, vec![1u8; 10]), make_migrated_state(&mut state)];
+        handle(&accounts);
+    }
+
+    #[test]
+    #[should_panic(expected = "Threshold cannot exceed member count")]
+    fn test_import_multisig_invalid_threshold_fails() {
+        let mut state = MultisigState::new([1u8; 32], 1, vec![[10u8; 32]]);
+        state.threshold = 2;
+        let accounts = vec![make_new_state(&state.create_key, vec![]), make_migrated_state(&mut state)];
+        handle(&accounts);
+    }
+
+    #[test]
+    #[should_panic(expected = "must be authorized")]
+    fn test_import_multisig_unauthorized_old_state_fails() {
+        let mut state = MultisigState::new([1u8; 32], 1, vec![[10u8; 32]]);
+        let mut old_state = make_migrated_state(&mut state);
+        old_state.is_authorized = false;
+        handle(&[make_new_state(&state.create_key, vec![]), old_state]);
+    }
+
+    #[test]
+    #[should_panic(expected = "has not been migrated")]
+    fn test_import_multisig_unmigrated_old_state_fails() {
+        let mut state = MultisigState::new([1u8; 32], 1, vec![[10u8; 32]]);
+        let mut old_state = make_migrated_state(&mut state);
+        state.migrated_to = None;
+        old_state.account.data = state.to_bytes().try_into().unwrap();
+        handle(&[make_new_state(&state.create_key, vec![]), old_state]);
+    }
+
+    #[test]
+    #[should_panic(expected = "not the state PDA of its program")]
+    fn test_import_multisig_old_state_at_wrong_address_fails() {
+        let mut state = MultisigState::new([1u8; 32], 1, vec![[10u8; 32]]);
+        let mut old_state = make_migrated_state(&mut state);
+        old_state.account.program_owner = [3u32; 8];
+        handle(&[make_new_state(&state.create_key, vec![]), old_state]);
+    }
+
+    #[test]
+    #[should_panic(expected = "not the state PDA of the migration target")]
+    fn test_import_multisig_wrong_new_state_fails() {
+        let mut state = MultisigState::new([1u8; 32], 1, vec![[10u8; 32]]);
+        let old_state = make_migrated_state(&mut state);
+        let mut new_state = make_new_state(&state.create_key, vec![]);
+        new_state.account_id = compute_multisig_state_pda(&NEW_PROGRAM, &[2u8; 32]);
+        handle(&[new_state, old_state]);
+    }
+}
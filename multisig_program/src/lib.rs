@@ -4,10 +4,21 @@ pub mod propose_config;
 pub mod approve;
 pub mod reject;
 pub mod execute;
+pub mod approve_and_execute;
+pub mod migrate_state;
+pub mod import_multisig;
+pub mod activate_proposal;
+pub mod assert_post_conditions;
+pub mod expire_proposal;
 
-use nssa_core::account::AccountWithMetadata;
-use nssa_core::program::{AccountPostState, ChainedCall};
-use multisig_core::{ConfigAction, Instruction};
+#[cfg(test)]
+mod test_utils;
+
+use nssa_core::account::{Account, AccountWithMetadata};
+use nssa_core::program::{AccountPostState, ChainedCall, ProgramId};
+use multisig_core::{
+    ConfigAction, Instruction, MultisigState, Proposal, compute_multisig_state_pda, compute_proposal_pda,
+};
 
 /// Main entry point called from the guest binary.
 pub fn process(
@@ -24,16 +35,30 @@ pub fn process(
         Instruction::Propose {
             target_program_id,
             target_instruction_data,
-            target_account_count,
+            target_account_ids,
             pda_seeds,
             authorized_indices,
+            memo,
+            content_hash,
+            draft,
+            depends_on,
+            recurrence,
+            post_conditions,
+            expiry,
         } => propose::handle(
             accounts,
             target_program_id,
             target_instruction_data,
-            *target_account_count,
+            target_account_ids,
             pda_seeds,
             authorized_indices,
+            memo,
+            *content_hash,
+            *draft,
+            *depends_on,
+            recurrence.clone(),
+            post_conditions,
+            expiry.clone(),
         ),
 
         Instruction::Approve { proposal_index } => {
@@ -44,20 +69,159 @@ pub fn process(
             reject::handle(accounts, *proposal_index)
         }
 
-        Instruction::Execute { proposal_index } => {
-            execute::handle(accounts, *proposal_index)
+        Instruction::Execute { proposal_index, post_states } => {
+            execute::handle(accounts, *proposal_index, post_states)
+        }
+
+        Instruction::ApproveAndExecute { proposal_index, post_states } => {
+            approve_and_execute::handle(accounts, *proposal_index, post_states)
+        }
+
+        Instruction::ProposeAddMember { new_member, memo, content_hash } => {
+            propose_config::handle(
+                accounts,
+                ConfigAction::AddMember { new_member: *new_member },
+                memo,
+                *content_hash,
+            )
+        }
+
+        Instruction::ProposeRemoveMember { member, memo, content_hash } => {
+            propose_config::handle(
+                accounts,
+                ConfigAction::RemoveMember { member: *member },
+                memo,
+                *content_hash,
+            )
+        }
+
+        Instruction::ProposeChangeThreshold { new_threshold, memo, content_hash } => {
+            propose_config::handle(
+                accounts,
+                ConfigAction::ChangeThreshold { new_threshold: *new_threshold },
+                memo,
+                *content_hash,
+            )
+        }
+
+        Instruction::ProposeSetPermissionlessExecution { enabled, memo, content_hash } => {
+            propose_config::handle(
+                accounts,
+                ConfigAction::SetPermissionlessExecution { enabled: *enabled },
+                memo,
+                *content_hash,
+            )
+        }
+
+        Instruction::MigrateState => migrate_state::handle(accounts),
+
+        Instruction::ProposeMigrateToProgram { new_program_id, handover_calls, memo, content_hash } => {
+            propose_config::handle(
+                accounts,
+                ConfigAction::MigrateToProgram {
+                    new_program_id: *new_program_id,
+                    handover_calls: handover_calls.clone(),
+                },
+                memo,
+                *content_hash,
+            )
+        }
+
+        Instruction::ImportMultisig => import_multisig::handle(accounts),
+
+        Instruction::ProposeSetRejectThreshold { reject_threshold, memo, content_hash } => {
+            propose_config::handle(
+                accounts,
+                ConfigAction::SetRejectThreshold { reject_threshold: *reject_threshold },
+                memo,
+                *content_hash,
+            )
         }
 
-        Instruction::ProposeAddMember { new_member } => {
-            propose_config::handle(accounts, ConfigAction::AddMember { new_member: *new_member })
+        Instruction::ActivateProposal { proposal_index } => {
+            activate_proposal::handle(accounts, *proposal_index)
         }
 
-        Instruction::ProposeRemoveMember { member } => {
-            propose_config::handle(accounts, ConfigAction::RemoveMember { member: *member })
+        Instruction::AssertPostConditions { conditions, snapshots } => {
+            assert_post_conditions::handle(accounts, conditions, snapshots)
         }
 
-        Instruction::ProposeChangeThreshold { new_threshold } => {
-            propose_config::handle(accounts, ConfigAction::ChangeThreshold { new_threshold: *new_threshold })
+        Instruction::ExpireProposal { proposal_index } => {
+            expire_proposal::handle(accounts, *proposal_index)
         }
     }
 }
+
+/// Check the state and proposal accounts of an instruction that writes the
+/// proposal back, and return this program's ID.
+///
+/// The program is not told its own ID, but the runtime only accepts writes to
+/// accounts the program owns, so the owner of the written proposal is our ID.
+/// Both accounts must then be owned by it and sit at the PDAs it derives,
+/// which rules out look-alike accounts created by another program.
+pub(crate) fn check_multisig_accounts(
+    multisig_account: &AccountWithMetadata,
+    proposal_account: &AccountWithMetadata,
+    state: &MultisigState,
+    proposal: &Proposal,
+) -> ProgramId {
+    let program_id = proposal_account.account.program_owner;
+    check_state_account(&program_id, multisig_account, state);
+    check_proposal_account(&program_id, proposal_account, proposal);
+    program_id
+}
+
+/// Require the multisig not to have been migrated away. A migration proposal
+/// itself is let through to report "already been migrated" when it executes.
+pub(crate) fn check_not_migrated(state: &MultisigState, proposal: &Proposal) {
+    if !matches!(proposal.config_action, Some(ConfigAction::MigrateToProgram { .. })) {
+        assert!(state.migrated_to.is_none(), "Multisig has been migrated to another program");
+    }
+}
+
+/// Require `account` to be the state PDA of `state` under `program_id`, owned by it.
+pub(crate) fn check_state_account(program_id: &ProgramId, account: &AccountWithMetadata, state: &MultisigState) {
+    assert_eq!(
+        &account.account.program_owner, program_id,
+        "Multisig state is not owned by this program"
+    );
+    assert_eq!(
+        account.account_id,
+        compute_multisig_state_pda(program_id, &state.create_key),
+        "Multisig state account is not this multisig's state PDA"
+    );
+}
+
+/// Require `account` to be the still-uninitialized PDA of proposal `index` of
+/// the multisig with `create_key` under `program_id`.
+///
+/// A proposal written anywhere else would use up the index but fail every
+/// later `check_proposal_account`, so it could never be voted on.
+pub(crate) fn check_new_proposal_account(
+    program_id: &ProgramId,
+    account: &AccountWithMetadata,
+    create_key: &[u8; 32],
+    index: u64,
+) {
+    assert!(account.account == Account::default(), "Proposal account must be uninitialized");
+    assert_eq!(
+        account.account_id,
+        compute_proposal_pda(program_id, create_key, index),
+        "Proposal account is not proposal #{}'s PDA",
+        index
+    );
+}
+
+/// Require `account` to be the PDA of `proposal` under `program_id`, owned by it.
+pub(crate) fn check_proposal_account(program_id: &ProgramId, account: &AccountWithMetadata, proposal: &Proposal) {
+    assert_eq!(
+        &account.account.program_owner, program_id,
+        "Proposal is not owned by this program"
+    );
+    assert_eq!(
+        account.account_id,
+        compute_proposal_pda(program_id, &proposal.multisig_create_key, proposal.index),
+        "Proposal account is not proposal #{}'s PDA",
+        proposal.index
+    );
+}
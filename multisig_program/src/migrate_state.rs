@@ -0,0 +1,243 @@
+// MigrateState handler — rewrites a multisig's accounts in the current schema version.
+//
+// This is the only handler that reads pre-discriminator layouts
+// (`from_legacy_bytes`); it re-encodes the accounts in place so every other
+// handler can read them. New fields take their default values until changed
+// by proposals.
+//
+// Expected accounts:
+// - accounts[0]: multisig_state PDA
+// - accounts[1]: member (must be authorized signer, must be member)
+// - accounts[2..]: proposal PDA accounts of this multisig to migrate (optional)
+
+use nssa_core::account::AccountWithMetadata;
+use nssa_core::program::{AccountPostState, ChainedCall};
+use multisig_core::{MultisigState, Proposal};
+
+pub fn handle(
+    accounts: &[AccountWithMetadata],
+) -> (Vec<AccountPostState>, Vec<ChainedCall>) {
+    assert!(accounts.len() >= 2, "MigrateState requires multisig_state + member accounts");
+
+    let multisig_account = &accounts[0];
+    let member_account = &accounts[1];
+    let proposal_accounts = &accounts[2..];
+
+    assert!(member_account.is_authorized, "Member must sign the transaction");
+
+    let state_data: Vec<u8> = multisig_account.account.data.clone().into();
+    let state = MultisigState::from_legacy_bytes(&state_data)
+        .expect("Failed to deserialize multisig state");
+
+    // The state is written back, so the runtime only accepts this call if this
+    // program owns it; its owner is our program ID.
+    let program_id = multisig_account.account.program_owner;
+    crate::check_state_account(&program_id, multisig_account, &state);
+
+    let member_id = *member_account.account_id.value();
+    assert!(state.is_member(&member_id), "Signer is not a multisig member");
+
+    let mut multisig_post = multisig_account.account.clone();
+    multisig_post.data = state.to_bytes().try_into().unwrap();
+
+    let mut post_states = vec![
+        AccountPostState::new(multisig_post),
+        AccountPostState::new(member_account.account.clone()),
+    ];
+
+    for proposal_account in proposal_accounts {
+        let proposal_data: Vec<u8> = proposal_account.account.data.clone().into();
+        let proposal = Proposal::from_legacy_bytes(&proposal_data)
+            .expect("Failed to deserialize proposal");
+        assert_eq!(proposal.multisig_create_key, state.create_key, "Proposal does not belong to this multisig");
+        crate::check_proposal_account(&program_id, proposal_account, &proposal);
+
+        let mut proposal_post = proposal_account.account.clone();
+        proposal_post.data = proposal.to_bytes().try_into().unwrap();
+        post_states.push(AccountPostState::new(proposal_post));
+    }
+
+    (post_states, vec![])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+    use nssa_core::program::ProgramId;
+    use multisig_core::{
+        ConfigAction, ProposalStatus, MULTISIG_STATE_DISCRIMINATOR, MULTISIG_STATE_VERSION,
+        PROPOSAL_DISCRIMINATOR, PROPOSAL_VERSION,
+    };
+
+    /// State as written before schema versioning (plain borsh, no version byte).
+    fn make_legacy_state(members: Vec<[u8; 32]>) -> Vec<u8> {
+        let count = members.len() as u8;
+        borsh::to_vec(&([0u8; 32], count, count, members, 3u64)).unwrap()
+    }
+
+    /// Config proposal as written before schema versioning.
+    fn make_legacy_proposal(proposer: [u8; 32]) -> Vec<u8> {
+        let target_program_id: ProgramId = [0u32; 8];
+        borsh::to_vec(&(
+            (3u64, proposer, [0u8; 32], target_program_id, Vec::<u32>::new(), 0u8),
+            (Vec::<[u8; 32]>::new(), Vec::<u8>::new(), vec![proposer], Vec::<[u8; 32]>::new()),
+            (ProposalStatus::Active, Some(ConfigAction::ChangeThreshold { new_threshold: 1 })),
+        )).unwrap()
+    }
+
+    #[test]
+    fn test_legacy_accounts_decode() {
+        let members = vec![[1u8; 32], [2u8; 32]];
+
+        let state = MultisigState::from_legacy_bytes(&make_legacy_state(members.clone())).unwrap();
+        assert_eq!(state.members, members);
+        assert_eq!(state.threshold, 2);
+        assert_eq!(state.transaction_index, 3);
+        assert!(!state.permissionless_execution);
+
+        let proposal = Proposal::from_legacy_bytes(&make_legacy_proposal([1u8; 32])).unwrap();
+        assert_eq!(proposal.index, 3);
+        assert_eq!(proposal.approved, vec![[1u8; 32]]);
+        assert_eq!(proposal.config_action, Some(ConfigAction::ChangeThreshold { new_threshold: 1 }));
+        assert!(proposal.memo.is_empty());
+    }
+
+    #[test]
+    fn test_legacy_accounts_rejected_by_regular_decode() {
+        assert!(MultisigState::from_bytes(&make_legacy_state(vec![[1u8; 32]])).is_err());
+        assert!(Proposal::from_bytes(&make_legacy_proposal([1u8; 32])).is_err());
+    }
+
+    #[test]
+    fn test_discriminator_prevents_type_confusion() {
+        let state = MultisigState::new([0u8; 32], 1, vec![[1u8; 32]]);
+        let proposal = Proposal::new_config(
+            1,
+            [1u8; 32],
+            [0u8; 32],
+            ConfigAction::ChangeThreshold { new_threshold: 1 },
+            String::new(),
+            None,
+        );
+
+        assert!(Proposal::from_bytes(&state.to_bytes()).is_err());
+        assert!(MultisigState::from_bytes(&proposal.to_bytes()).is_err());
+        assert!(Proposal::from_legacy_bytes(&state.to_bytes()).is_err());
+        assert!(MultisigState::from_legacy_bytes(&proposal.to_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_proposal_bytes_never_decode_as_state() {
+        let proposal = Proposal::new_config(
+            1,
+            [1u8; 32],
+            [0u8; 32],
+            ConfigAction::ChangeThreshold { new_threshold: 1 },
+            String::new(),
+            None,
+        );
+        let legacy_proposal = make_legacy_proposal([1u8; 32]);
+
+        for data in [proposal.to_bytes(), legacy_proposal] {
+            assert!(MultisigState::from_bytes(&data).is_err());
+            assert!(MultisigState::from_legacy_bytes(&data).is_err());
+        }
+    }
+
+    #[test]
+    fn test_discriminated_data_never_falls_back_to_legacy_layout() {
+        // A discriminator followed by an unknown version must not be retried
+        // as plain borsh.
+        let mut data = MULTISIG_STATE_DISCRIMINATOR.to_vec();
+        data.push(MULTISIG_STATE_VERSION + 1);
+        data.extend(make_legacy_state(vec![[1u8; 32]]));
+        assert!(MultisigState::from_legacy_bytes(&data).is_err());
+
+        let mut data = PROPOSAL_DISCRIMINATOR.to_vec();
+        data.push(PROPOSAL_VERSION + 1);
+        data.extend(make_legacy_proposal([1u8; 32]));
+        assert!(Proposal::from_legacy_bytes(&data).is_err());
+    }
+
+    #[test]
+    fn test_migrate_rewrites_state_and_proposals() {
+        let members = vec![[1u8; 32], [2u8; 32]];
+
+        let accounts = vec![
+            make_account(&state_id(), make_legacy_state(members.clone()), false),
+            make_account(&[2u8; 32], vec![], true),
+            make_account(&proposal_id(3), make_legacy_proposal([1u8; 32]), false),
+        ];
+
+        let (post_states, chained) = handle(&accounts);
+
+        assert!(chained.is_empty());
+        assert_eq!(post_states.len(), 3);
+
+        let state_data = Vec::from(post_states[0].account().data.clone());
+        assert_eq!(state_data[..8], MULTISIG_STATE_DISCRIMINATOR);
+        assert_eq!(state_data[8], MULTISIG_STATE_VERSION);
+        let state = MultisigState::from_legacy_bytes(&state_data).unwrap();
+        assert_eq!(state.members, members);
+        assert_eq!(state.transaction_index, 3);
+
+        let proposal_data = Vec::from(post_states[2].account().data.clone());
+        assert_eq!(proposal_data[..8], PROPOSAL_DISCRIMINATOR);
+        assert_eq!(proposal_data[8], PROPOSAL_VERSION);
+        let proposal = Proposal::from_legacy_bytes(&proposal_data).unwrap();
+        assert_eq!(proposal.index, 3);
+        assert_eq!(proposal.status, ProposalStatus::Active);
+    }
+
+    #[test]
+    fn test_migrate_current_version_is_idempotent() {
+        let members = vec![[1u8; 32], [2u8; 32]];
+        let state_data = MultisigState::new([0u8; 32], 2, members).to_bytes();
+
+        let accounts = vec![
+            make_account(&state_id(), state_data.clone(), false),
+            make_account(&[1u8; 32], vec![], true),
+        ];
+
+        let (post_states, _) = handle(&accounts);
+        assert_eq!(Vec::from(post_states[0].account().data.clone()), state_data);
+    }
+
+    #[test]
+    #[should_panic(expected = "not a multisig member")]
+    fn test_migrate_non_member_fails() {
+        let accounts = vec![
+            make_account(&state_id(), make_legacy_state(vec![[1u8; 32]]), false),
+            make_account(&[99u8; 32], vec![], true),
+        ];
+
+        handle(&accounts);
+    }
+
+    #[test]
+    #[should_panic(expected = "not this multisig's state PDA")]
+    fn test_migrate_state_at_wrong_address_fails() {
+        let accounts = vec![
+            make_account(&[10u8; 32], make_legacy_state(vec![[1u8; 32]]), false),
+            make_account(&[1u8; 32], vec![], true),
+        ];
+
+        handle(&accounts);
+    }
+
+    #[test]
+    #[should_panic(expected = "Proposal is not owned by this program")]
+    fn test_migrate_foreign_proposal_fails() {
+        let mut proposal_account = make_account(&proposal_id(3), make_legacy_proposal([1u8; 32]), false);
+        proposal_account.account.program_owner = [66u32; 8];
+
+        let accounts = vec![
+            make_account(&state_id(), make_legacy_state(vec![[1u8; 32]]), false),
+            make_account(&[1u8; 32], vec![], true),
+            proposal_account,
+        ];
+
+        handle(&accounts);
+    }
+}
@@ -3,21 +3,31 @@
 // Expected accounts:
 // - accounts[0]: multisig_state PDA (read membership, increment tx_index)
 // - accounts[1]: proposer (must be authorized signer, must be member)
-// - accounts[2]: proposal PDA account (must be Account::default() = uninitialized)
+// - accounts[2]: PDA of the next proposal index (must be Account::default() = uninitialized)
 
 use nssa_core::account::{Account, AccountWithMetadata};
 use nssa_core::program::{AccountPostState, ChainedCall, InstructionData, ProgramId};
-use multisig_core::{MultisigState, Proposal};
+use multisig_core::{
+    Expiry, MultisigState, PostCondition, Proposal, ProposalStatus, Recurrence, MAX_MEMO_LEN, vault_pda_seed_bytes,
+};
 
 pub fn handle(
     accounts: &[AccountWithMetadata],
     target_program_id: &ProgramId,
     target_instruction_data: &InstructionData,
-    target_account_count: u8,
+    target_account_ids: &[[u8; 32]],
     pda_seeds: &[[u8; 32]],
     authorized_indices: &[u8],
+    memo: &str,
+    content_hash: Option<[u8; 32]>,
+    draft: bool,
+    depends_on: Option<u64>,
+    recurrence: Option<Recurrence>,
+    post_conditions: &[PostCondition],
+    expiry: Option<Expiry>,
 ) -> (Vec<AccountPostState>, Vec<ChainedCall>) {
     assert!(accounts.len() >= 3, "Propose requires multisig_state + proposer + proposal accounts");
+    assert!(memo.len() <= MAX_MEMO_LEN, "Memo exceeds {} bytes", MAX_MEMO_LEN);
 
     let multisig_account = &accounts[0];
     let proposer_account = &accounts[1];
@@ -25,41 +35,84 @@ pub fn handle(
 
     assert!(proposer_account.is_authorized, "Proposer must sign the transaction");
 
-    // Proposal account must be uninitialized
-    assert!(
-        proposal_account.account == Account::default(),
-        "Proposal account must be uninitialized"
-    );
-
     // Read and update multisig state (increment transaction_index)
     let state_data: Vec<u8> = multisig_account.account.data.clone().into();
-    let mut state: MultisigState = borsh::from_slice(&state_data)
+    let mut state = MultisigState::from_bytes(&state_data)
         .expect("Failed to deserialize multisig state");
 
+    // The proposal account is not ours yet, so our program ID is the state account's owner
+    let program_id = multisig_account.account.program_owner;
+    crate::check_state_account(&program_id, multisig_account, &state);
+
+    assert!(state.migrated_to.is_none(), "Multisig has been migrated to another program");
+
     let proposer_id = *proposer_account.account_id.value();
     assert!(state.is_member(&proposer_id), "Proposer is not a multisig member");
 
+    // ChainedCall seeds are derived from this program's ID, which every multisig
+    // shares — only the proposing multisig's own vault may be authorized
+    let own_vault_seed = vault_pda_seed_bytes(&state.create_key);
+    assert!(
+        pda_seeds.iter().all(|seed| *seed == own_vault_seed),
+        "Proposal may only use this multisig's vault PDA seed"
+    );
+
     let proposal_index = state.next_proposal_index();
+    crate::check_new_proposal_account(&program_id, proposal_account, &state.create_key, proposal_index);
 
     // Create the proposal
-    let proposal = Proposal::new(
+    let mut proposal = Proposal::new(
         proposal_index,
         proposer_id,
         state.create_key,
         target_program_id.clone(),
         target_instruction_data.clone(),
-        target_account_count,
+        target_account_ids.to_vec(),
         pda_seeds.to_vec(),
         authorized_indices.to_vec(),
+        memo.to_string(),
+        content_hash,
     );
+    if draft {
+        proposal.status = ProposalStatus::Draft;
+    }
+    if let Some(dependency) = depends_on {
+        // Earlier proposals only, so dependencies can never form a cycle
+        assert!(
+            dependency >= 1 && dependency < proposal_index,
+            "Proposal can only depend on an earlier proposal"
+        );
+        proposal.depends_on = Some(dependency);
+    }
+    if let Some(recurrence) = &recurrence {
+        assert!(recurrence.interval >= 1, "Recurrence interval must be at least 1 second");
+        assert!(recurrence.max_runs >= 1, "Recurrence must allow at least one run");
+    }
+    if let (Some(recurrence), Some(expiry)) = (&recurrence, &expiry) {
+        assert_eq!(
+            recurrence.clock_account_id, expiry.clock_account_id,
+            "Recurrence and expiry must use the same clock account"
+        );
+    }
+    proposal.recurrence = recurrence;
+    proposal.expiry = expiry;
+    for condition in post_conditions {
+        assert!(
+            (condition.account_index() as usize) < target_account_ids.len(),
+            "Post-condition refers to target account {}, but the proposal has {}",
+            condition.account_index(),
+            target_account_ids.len()
+        );
+    }
+    proposal.post_conditions = post_conditions.to_vec();
 
     // Serialize updated multisig state (with incremented tx_index)
-    let state_bytes = borsh::to_vec(&state).unwrap();
+    let state_bytes = state.to_bytes();
     let mut multisig_post = multisig_account.account.clone();
     multisig_post.data = state_bytes.try_into().unwrap();
 
     // Serialize proposal into new account and claim it
-    let proposal_bytes = borsh::to_vec(&proposal).unwrap();
+    let proposal_bytes = proposal.to_bytes();
     let mut proposal_post = Account::default();
     proposal_post.data = proposal_bytes.try_into().unwrap();
 
@@ -78,32 +131,18 @@ pub fn handle(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use nssa_core::account::{Account, AccountId};
+    use crate::test_utils::*;
     use multisig_core::MultisigState;
 
-    fn make_account(id: &[u8; 32], data: Vec<u8>, authorized: bool) -> AccountWithMetadata {
-        let mut account = Account::default();
-        account.data = data.try_into().unwrap();
-        AccountWithMetadata {
-            account_id: AccountId::new(*id),
-            account,
-            is_authorized: authorized,
-        }
-    }
-
-    fn make_state(threshold: u8, members: Vec<[u8; 32]>) -> Vec<u8> {
-        borsh::to_vec(&MultisigState::new([0u8; 32], threshold, members)).unwrap()
-    }
-
     #[test]
     fn test_propose_creates_proposal_and_increments_index() {
         let members = vec![[1u8; 32], [2u8; 32], [3u8; 32]];
         let state_data = make_state(2, members.clone());
 
         let accounts = vec![
-            make_account(&[10u8; 32], state_data, false), // multisig state
+            make_account(&state_id(), state_data, false), // multisig state
             make_account(&[1u8; 32], vec![], true),         // proposer (member)
-            make_account(&[20u8; 32], vec![], false),        // proposal PDA (uninitialized)
+            make_account(&proposal_id(1), vec![], false),        // proposal PDA (uninitialized)
         ];
 
         let program_id: ProgramId = [42u32; 8];
@@ -111,28 +150,74 @@ mod tests {
             &accounts,
             &program_id,
             &vec![0u32],
-            1,
+            &[[30u8; 32]],
             &[],
             &[],
+            "Pay contractor invoice #12",
+            Some([7u8; 32]),
+            false,
+            None,
+            None,
+            &[PostCondition::MaxBalanceDecrease { account_index: 0, max_decrease: 100 }],
+            None,
         );
 
         assert!(chained.is_empty());
         assert_eq!(post_states.len(), 3);
 
         // Multisig state should have incremented tx index
-        let state: MultisigState = borsh::from_slice(
+        let state = MultisigState::from_bytes(
             &Vec::from(post_states[0].account().data.clone())
         ).unwrap();
         assert_eq!(state.transaction_index, 1);
 
         // Proposal should exist with proposer auto-approved
-        let proposal: Proposal = borsh::from_slice(
+        let proposal = Proposal::from_bytes(
             &Vec::from(post_states[2].account().data.clone())
         ).unwrap();
         assert_eq!(proposal.index, 1);
         assert_eq!(proposal.proposer, [1u8; 32]);
         assert_eq!(proposal.approved, vec![[1u8; 32]]);
-        assert_eq!(proposal.status, multisig_core::ProposalStatus::Active);
+        assert_eq!(proposal.status, ProposalStatus::Active);
+        assert_eq!(proposal.memo, "Pay contractor invoice #12");
+        assert_eq!(proposal.content_hash, Some([7u8; 32]));
+        assert_eq!(proposal.target_account_ids, vec![[30u8; 32]]);
+        assert_eq!(proposal.post_conditions.len(), 1);
+    }
+
+    #[test]
+    fn test_propose_draft() {
+        let state_data = make_state(2, vec![[1u8; 32], [2u8; 32]]);
+
+        let accounts = vec![
+            make_account(&state_id(), state_data, false),
+            make_account(&[1u8; 32], vec![], true),
+            make_account(&proposal_id(1), vec![], false),
+        ];
+
+        let program_id: ProgramId = [42u32; 8];
+        let (post_states, _) = handle(&accounts, &program_id, &vec![0u32], &[[30u8; 32]], &[], &[], "", None, true, None, None, &[], None);
+
+        let proposal = Proposal::from_bytes(
+            &Vec::from(post_states[2].account().data.clone())
+        ).unwrap();
+        assert_eq!(proposal.status, ProposalStatus::Draft);
+    }
+
+    #[test]
+    #[should_panic(expected = "can only depend on an earlier proposal")]
+    fn test_propose_depending_on_itself_fails() {
+        let state_data = make_state(2, vec![[1u8; 32], [2u8; 32]]);
+
+        let accounts = vec![
+            make_account(&state_id(), state_data, false),
+            make_account(&[1u8; 32], vec![], true),
+            make_account(&proposal_id(1), vec![], false),
+        ];
+
+        // The new proposal gets index 1
+        let program_id: ProgramId = [42u32; 8];
+        handle(&accounts, &program_id, &vec![0u32], &[[30u8; 32]], &[], &[], "", None, false, Some(1), None, &[], None);
     }
 
     #[test]
@@ -142,13 +227,13 @@ mod tests {
         let state_data = make_state(2, members);
 
         let accounts = vec![
-            make_account(&[10u8; 32], state_data, false),
+            make_account(&state_id(), state_data, false),
             make_account(&[99u8; 32], vec![], true), // NOT a member
-            make_account(&[20u8; 32], vec![], false),
+            make_account(&proposal_id(1), vec![], false),
         ];
 
         let program_id: ProgramId = [42u32; 8];
-        handle(&accounts, &program_id, &vec![0u32], 1, &[], &[]);
+        handle(&accounts, &program_id, &vec![0u32], &[[30u8; 32]], &[], &[], "", None, false, None, None, &[], None);
     }
 
     #[test]
@@ -158,12 +243,127 @@ mod tests {
         let state_data = make_state(2, members);
 
         let accounts = vec![
-            make_account(&[10u8; 32], state_data, false),
+            make_account(&state_id(), state_data, false),
             make_account(&[1u8; 32], vec![], false), // not authorized
-            make_account(&[20u8; 32], vec![], false),
+            make_account(&proposal_id(1), vec![], false),
+        ];
+
+        let program_id: ProgramId = [42u32; 8];
+        handle(&accounts, &program_id, &vec![0u32], &[[30u8; 32]], &[], &[], "", None, false, None, None, &[], None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Memo exceeds")]
+    fn test_propose_memo_too_long_fails() {
+        let members = vec![[1u8; 32], [2u8; 32]];
+        let state_data = make_state(2, members);
+
+        let accounts = vec![
+            make_account(&state_id(), state_data, false),
+            make_account(&[1u8; 32], vec![], true),
+            make_account(&proposal_id(1), vec![], false),
+        ];
+
+        let memo = "x".repeat(MAX_MEMO_LEN + 1);
+        let program_id: ProgramId = [42u32; 8];
+        handle(&accounts, &program_id, &vec![0u32], &[[30u8; 32]], &[], &[], &memo, None, false, None, None, &[], None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Post-condition refers to target account 1")]
+    fn test_propose_post_condition_out_of_range_fails() {
+        let state_data = make_state(2, vec![[1u8; 32], [2u8; 32]]);
+
+        let accounts = vec![
+            make_account(&state_id(), state_data, false),
+            make_account(&[1u8; 32], vec![], true),
+            make_account(&proposal_id(1), vec![], false),
+        ];
+
+        let program_id: ProgramId = [42u32; 8];
+        let conditions = [PostCondition::AccountExists { account_index: 1 }];
+        handle(&accounts, &program_id, &vec![0u32], &[[30u8; 32]], &[], &[], "", None, false, None, None, &conditions, None);
+    }
+
+    #[test]
+    fn test_propose_with_own_vault_seed() {
+        let state_data = make_state(2, vec![[1u8; 32], [2u8; 32]]);
+
+        let accounts = vec![
+            make_account(&state_id(), state_data, false),
+            make_account(&[1u8; 32], vec![], true),
+            make_account(&proposal_id(1), vec![], false),
+        ];
+
+        let program_id: ProgramId = [42u32; 8];
+        let seeds = [vault_pda_seed_bytes(&[0u8; 32])];
+        let (post_states, _) = handle(&accounts, &program_id, &vec![0u32], &[[30u8; 32]], &seeds, &[0], "", None, false, None, None, &[], None);
+
+        let proposal = Proposal::from_bytes(&Vec::from(post_states[2].account().data.clone())).unwrap();
+        assert_eq!(proposal.pda_seeds, seeds);
+    }
+
+    #[test]
+    #[should_panic(expected = "may only use this multisig's vault PDA seed")]
+    fn test_propose_with_other_multisigs_vault_seed_fails() {
+        // Multisig A (create key [0; 32]) tries to authorize multisig B's vault
+        let state_data = make_state(1, vec![[1u8; 32]]);
+
+        let accounts = vec![
+            make_account(&state_id(), state_data, false),
+            make_account(&[1u8; 32], vec![], true),
+            make_account(&proposal_id(1), vec![], false),
+        ];
+
+        let program_id: ProgramId = [42u32; 8];
+        let victim_seed = vault_pda_seed_bytes(&[7u8; 32]);
+        handle(&accounts, &program_id, &vec![0u32], &[[30u8; 32]], &[victim_seed], &[0], "", None, false, None, None, &[], None);
+    }
+
+    #[test]
+    #[should_panic(expected = "is not proposal #1's PDA")]
+    fn test_propose_at_wrong_address_fails() {
+        let state_data = make_state(1, vec![[1u8; 32]]);
+
+        let accounts = vec![
+            make_account(&state_id(), state_data, false),
+            make_account(&[1u8; 32], vec![], true),
+            make_account(&proposal_id(2), vec![], false), // skips ahead of the next index
+        ];
+
+        let program_id: ProgramId = [42u32; 8];
+        handle(&accounts, &program_id, &vec![0u32], &[[30u8; 32]], &[], &[0], "", None, false, None, None, &[], None);
+    }
+
+    #[test]
+    #[should_panic(expected = "not this multisig's state PDA")]
+    fn test_propose_with_state_at_wrong_address_fails() {
+        let state_data = make_state(1, vec![[1u8; 32]]);
+
+        let accounts = vec![
+            make_account(&[10u8; 32], state_data, false),
+            make_account(&[1u8; 32], vec![], true),
+            make_account(&proposal_id(1), vec![], false),
+        ];
+
+        let program_id: ProgramId = [42u32; 8];
+        handle(&accounts, &program_id, &vec![0u32], &[[30u8; 32]], &[], &[0], "", None, false, None, None, &[], None);
+    }
+
+    #[test]
+    #[should_panic(expected = "must use the same clock account")]
+    fn test_propose_recurrence_and_expiry_with_different_clocks_fails() {
+        let state_data = make_state(1, vec![[1u8; 32]]);
+
+        let accounts = vec![
+            make_account(&state_id(), state_data, false),
+            make_account(&[1u8; 32], vec![], true),
+            make_account(&proposal_id(1), vec![], false),
         ];
 
         let program_id: ProgramId = [42u32; 8];
-        handle(&accounts, &program_id, &vec![0u32], 1, &[], &[]);
+        let recurrence = Recurrence { clock_account_id: [50u8; 32], interval: 100, max_runs: 2 };
+        let expiry = Expiry { clock_account_id: [51u8; 32], expires_at: 1_000 };
+        handle(&accounts, &program_id, &vec![0u32], &[[30u8; 32]], &[], &[0], "", None, false, None, Some(recurrence), &[], Some(expiry));
     }
 }
@@ -1,19 +1,23 @@
-// ProposeConfig handler — creates a config change proposal (add/remove member, change threshold).
+// ProposeConfig handler — creates a config change proposal (add/remove member, change threshold,
+// toggle permissionless execution, set the reject threshold, migrate to a new program).
 //
 // Expected accounts:
 // - accounts[0]: multisig_state PDA (read membership, increment tx_index)
 // - accounts[1]: proposer (must be authorized signer, must be member)
-// - accounts[2]: proposal PDA account (must be Account::default() = uninitialized)
+// - accounts[2]: PDA of the next proposal index (must be Account::default() = uninitialized)
 
 use nssa_core::account::{Account, AccountWithMetadata};
 use nssa_core::program::{AccountPostState, ChainedCall};
-use multisig_core::{ConfigAction, MultisigState, Proposal};
+use multisig_core::{ConfigAction, MultisigState, Proposal, MAX_MEMO_LEN};
 
 pub fn handle(
     accounts: &[AccountWithMetadata],
     config_action: ConfigAction,
+    memo: &str,
+    content_hash: Option<[u8; 32]>,
 ) -> (Vec<AccountPostState>, Vec<ChainedCall>) {
     assert!(accounts.len() >= 3, "ProposeConfig requires multisig_state + proposer + proposal accounts");
+    assert!(memo.len() <= MAX_MEMO_LEN, "Memo exceeds {} bytes", MAX_MEMO_LEN);
 
     let multisig_account = &accounts[0];
     let proposer_account = &accounts[1];
@@ -21,15 +25,16 @@ pub fn handle(
 
     assert!(proposer_account.is_authorized, "Proposer must sign the transaction");
 
-    assert!(
-        proposal_account.account == Account::default(),
-        "Proposal account must be uninitialized"
-    );
-
     let state_data: Vec<u8> = multisig_account.account.data.clone().into();
-    let mut state: MultisigState = borsh::from_slice(&state_data)
+    let mut state = MultisigState::from_bytes(&state_data)
         .expect("Failed to deserialize multisig state");
 
+    // The proposal account is not ours yet, so our program ID is the state account's owner
+    let program_id = multisig_account.account.program_owner;
+    crate::check_state_account(&program_id, multisig_account, &state);
+
+    assert!(state.migrated_to.is_none(), "Multisig has been migrated to another program");
+
     let proposer_id = *proposer_account.account_id.value();
     assert!(state.is_member(&proposer_id), "Proposer is not a multisig member");
 
@@ -45,24 +50,42 @@ pub fn handle(
         ConfigAction::ChangeThreshold { new_threshold } => {
             assert!(*new_threshold >= 1, "Threshold must be at least 1");
         }
+        ConfigAction::SetPermissionlessExecution { .. } => {}
+        ConfigAction::SetRejectThreshold { reject_threshold } => {
+            if let Some(cutoff) = reject_threshold {
+                assert!(*cutoff >= 1, "Reject threshold must be at least 1");
+            }
+        }
+        ConfigAction::MigrateToProgram { new_program_id, handover_calls } => {
+            assert_ne!(new_program_id, &program_id, "Cannot migrate to the current program");
+            for call in handover_calls {
+                assert!(
+                    call.authorized_indices.iter().all(|i| (*i as usize) < call.account_ids.len()),
+                    "Handover call authorized index out of range"
+                );
+            }
+        }
     }
 
     let proposal_index = state.next_proposal_index();
+    crate::check_new_proposal_account(&program_id, proposal_account, &state.create_key, proposal_index);
 
     let proposal = Proposal::new_config(
         proposal_index,
         proposer_id,
         state.create_key,
         config_action,
+        memo.to_string(),
+        content_hash,
     );
 
     // Serialize updated multisig state
-    let state_bytes = borsh::to_vec(&state).unwrap();
+    let state_bytes = state.to_bytes();
     let mut multisig_post = multisig_account.account.clone();
     multisig_post.data = state_bytes.try_into().unwrap();
 
     // Serialize proposal into new account
-    let proposal_bytes = borsh::to_vec(&proposal).unwrap();
+    let proposal_bytes = proposal.to_bytes();
     let mut proposal_post = Account::default();
     proposal_post.data = proposal_bytes.try_into().unwrap();
 
@@ -81,45 +104,31 @@ pub fn handle(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use nssa_core::account::{Account, AccountId};
+    use crate::test_utils::*;
     use multisig_core::MultisigState;
 
-    fn make_account(id: &[u8; 32], data: Vec<u8>, authorized: bool) -> AccountWithMetadata {
-        let mut account = Account::default();
-        account.data = data.try_into().unwrap();
-        AccountWithMetadata {
-            account_id: AccountId::new(*id),
-            account,
-            is_authorized: authorized,
-        }
-    }
-
-    fn make_state(threshold: u8, members: Vec<[u8; 32]>) -> Vec<u8> {
-        borsh::to_vec(&MultisigState::new([0u8; 32], threshold, members)).unwrap()
-    }
-
     #[test]
     fn test_propose_add_member() {
         let members = vec![[1u8; 32], [2u8; 32], [3u8; 32]];
         let state_data = make_state(2, members);
 
         let accounts = vec![
-            make_account(&[10u8; 32], state_data, false),
+            make_account(&state_id(), state_data, false),
             make_account(&[1u8; 32], vec![], true),
-            make_account(&[20u8; 32], vec![], false),
+            make_account(&proposal_id(1), vec![], false),
         ];
 
         let action = ConfigAction::AddMember { new_member: [4u8; 32] };
-        let (post_states, chained) = handle(&accounts, action);
+        let (post_states, chained) = handle(&accounts, action, "", None);
 
         assert!(chained.is_empty());
         assert_eq!(post_states.len(), 3);
 
-        let proposal: Proposal = borsh::from_slice(
+        let proposal = Proposal::from_bytes(
             &Vec::from(post_states[2].account().data.clone())
         ).unwrap();
         assert_eq!(proposal.config_action, Some(ConfigAction::AddMember { new_member: [4u8; 32] }));
-        assert_eq!(proposal.target_account_count, 0);
+        assert!(proposal.target_account_ids.is_empty());
     }
 
     #[test]
@@ -128,16 +137,16 @@ mod tests {
         let state_data = make_state(2, members);
 
         let accounts = vec![
-            make_account(&[10u8; 32], state_data, false),
+            make_account(&state_id(), state_data, false),
             make_account(&[1u8; 32], vec![], true),
-            make_account(&[20u8; 32], vec![], false),
+            make_account(&proposal_id(1), vec![], false),
         ];
 
         let action = ConfigAction::RemoveMember { member: [2u8; 32] };
-        let (post_states, chained) = handle(&accounts, action);
+        let (post_states, chained) = handle(&accounts, action, "", None);
 
         assert!(chained.is_empty());
-        let proposal: Proposal = borsh::from_slice(
+        let proposal = Proposal::from_bytes(
             &Vec::from(post_states[2].account().data.clone())
         ).unwrap();
         assert_eq!(proposal.config_action, Some(ConfigAction::RemoveMember { member: [2u8; 32] }));
@@ -149,18 +158,19 @@ mod tests {
         let state_data = make_state(2, members);
 
         let accounts = vec![
-            make_account(&[10u8; 32], state_data, false),
+            make_account(&state_id(), state_data, false),
             make_account(&[1u8; 32], vec![], true),
-            make_account(&[20u8; 32], vec![], false),
+            make_account(&proposal_id(1), vec![], false),
         ];
 
         let action = ConfigAction::ChangeThreshold { new_threshold: 3 };
-        let (post_states, _) = handle(&accounts, action);
+        let (post_states, _) = handle(&accounts, action, "Raise threshold to 3", None);
 
-        let proposal: Proposal = borsh::from_slice(
+        let proposal = Proposal::from_bytes(
             &Vec::from(post_states[2].account().data.clone())
         ).unwrap();
         assert_eq!(proposal.config_action, Some(ConfigAction::ChangeThreshold { new_threshold: 3 }));
+        assert_eq!(proposal.memo, "Raise threshold to 3");
     }
 
     #[test]
@@ -170,12 +180,12 @@ mod tests {
         let state_data = make_state(2, members);
 
         let accounts = vec![
-            make_account(&[10u8; 32], state_data, false),
+            make_account(&state_id(), state_data, false),
             make_account(&[1u8; 32], vec![], true),
-            make_account(&[20u8; 32], vec![], false),
+            make_account(&proposal_id(1), vec![], false),
         ];
 
-        handle(&accounts, ConfigAction::AddMember { new_member: [2u8; 32] });
+        handle(&accounts, ConfigAction::AddMember { new_member: [2u8; 32] }, "", None);
     }
 
     #[test]
@@ -185,12 +195,12 @@ mod tests {
         let state_data = make_state(2, members);
 
         let accounts = vec![
-            make_account(&[10u8; 32], state_data, false),
+            make_account(&state_id(), state_data, false),
             make_account(&[1u8; 32], vec![], true),
-            make_account(&[20u8; 32], vec![], false),
+            make_account(&proposal_id(1), vec![], false),
         ];
 
-        handle(&accounts, ConfigAction::RemoveMember { member: [99u8; 32] });
+        handle(&accounts, ConfigAction::RemoveMember { member: [99u8; 32] }, "", None);
     }
 
     #[test]
@@ -200,12 +210,12 @@ mod tests {
         let state_data = make_state(2, members);
 
         let accounts = vec![
-            make_account(&[10u8; 32], state_data, false),
+            make_account(&state_id(), state_data, false),
             make_account(&[1u8; 32], vec![], true),
-            make_account(&[20u8; 32], vec![], false),
+            make_account(&proposal_id(1), vec![], false),
         ];
 
-        handle(&accounts, ConfigAction::ChangeThreshold { new_threshold: 0 });
+        handle(&accounts, ConfigAction::ChangeThreshold { new_threshold: 0 }, "", None);
     }
 
     #[test]
@@ -215,11 +225,57 @@ mod tests {
         let state_data = make_state(2, members);
 
         let accounts = vec![
-            make_account(&[10u8; 32], state_data, false),
+            make_account(&state_id(), state_data, false),
             make_account(&[99u8; 32], vec![], true),
-            make_account(&[20u8; 32], vec![], false),
+            make_account(&proposal_id(1), vec![], false),
+        ];
+
+        handle(&accounts, ConfigAction::AddMember { new_member: [4u8; 32] }, "", None);
+    }
+
+    #[test]
+    #[should_panic(expected = "migrated to another program")]
+    fn test_propose_config_after_migration_fails() {
+        let mut state = MultisigState::new([0u8; 32], 1, vec![[1u8; 32], [2u8; 32]]);
+        state.migrated_to = Some([9u32; 8]);
+
+        let accounts = vec![
+            make_account(&state_id(), state.to_bytes(), false),
+            make_account(&[1u8; 32], vec![], true),
+            make_account(&proposal_id(1), vec![], false),
+        ];
+
+        handle(&accounts, ConfigAction::AddMember { new_member: [4u8; 32] }, "", None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Cannot migrate to the current program")]
+    fn test_propose_migration_to_current_program_fails() {
+        let accounts = vec![
+            make_account(&state_id(), make_state(1, vec![[1u8; 32], [2u8; 32]]), false),
+            make_account(&[1u8; 32], vec![], true),
+            make_account(&proposal_id(1), vec![], false),
+        ];
+
+        let action = ConfigAction::MigrateToProgram {
+            new_program_id: Account::default().program_owner,
+            handover_calls: vec![],
+        };
+        handle(&accounts, action, "", None);
+    }
+
+    #[test]
+    #[should_panic(expected = "is not proposal #2's PDA")]
+    fn test_propose_config_at_used_address_fails() {
+        let mut state = MultisigState::new([0u8; 32], 1, vec![[1u8; 32], [2u8; 32]]);
+        state.transaction_index = 1;
+
+        let accounts = vec![
+            make_account(&state_id(), state.to_bytes(), false),
+            make_account(&[1u8; 32], vec![], true),
+            make_account(&proposal_id(1), vec![], false), // index 1 is taken
         ];
 
-        handle(&accounts, ConfigAction::AddMember { new_member: [4u8; 32] });
+        handle(&accounts, ConfigAction::AddMember { new_member: [4u8; 32] }, "", None);
     }
 }
@@ -7,7 +7,7 @@
 
 use nssa_core::account::AccountWithMetadata;
 use nssa_core::program::{AccountPostState, ChainedCall};
-use multisig_core::{MultisigState, Proposal, ProposalStatus};
+use multisig_core::{MultisigState, Proposal, ProposalEvent};
 
 pub fn handle(
     accounts: &[AccountWithMetadata],
@@ -22,32 +22,26 @@ pub fn handle(
     assert!(rejector_account.is_authorized, "Rejector must sign the transaction");
 
     let state_data: Vec<u8> = multisig_account.account.data.clone().into();
-    let state: MultisigState = borsh::from_slice(&state_data)
+    let state = MultisigState::from_bytes(&state_data)
         .expect("Failed to deserialize multisig state");
 
     let rejector_id = *rejector_account.account_id.value();
     assert!(state.is_member(&rejector_id), "Rejector is not a multisig member");
 
-    let threshold = state.threshold;
-    let member_count = state.member_count;
-
     // Read and update proposal
     let proposal_data: Vec<u8> = proposal_account.account.data.clone().into();
-    let mut proposal: Proposal = borsh::from_slice(&proposal_data)
+    let mut proposal = Proposal::from_bytes(&proposal_data)
         .expect("Failed to deserialize proposal");
 
     assert_eq!(proposal.multisig_create_key, state.create_key, "Proposal does not belong to this multisig");
-    assert_eq!(proposal.status, ProposalStatus::Active, "Proposal is not active");
-
-    let is_new = proposal.reject(rejector_id);
-    assert!(is_new, "Member has already rejected this proposal");
-
-    if proposal.is_dead(threshold, member_count) {
-        proposal.status = ProposalStatus::Rejected;
+    crate::check_multisig_accounts(multisig_account, proposal_account, &state, &proposal);
+    // Marks the proposal Rejected once the threshold is out of reach
+    if let Err(e) = proposal.apply(&state, ProposalEvent::Reject(rejector_id)) {
+        panic!("{}", e);
     }
 
     // Write back proposal
-    let proposal_bytes = borsh::to_vec(&proposal).unwrap();
+    let proposal_bytes = proposal.to_bytes();
     let mut proposal_post = proposal_account.account.clone();
     proposal_post.data = proposal_bytes.try_into().unwrap();
 
@@ -67,56 +61,24 @@ pub fn handle(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use nssa_core::account::{Account, AccountId};
-    use nssa_core::program::ProgramId;
-    use multisig_core::MultisigState;
-
-    fn make_account(id: &[u8; 32], data: Vec<u8>, authorized: bool) -> AccountWithMetadata {
-        let mut account = Account::default();
-        account.data = data.try_into().unwrap();
-        AccountWithMetadata {
-            account_id: AccountId::new(*id),
-            account,
-            is_authorized: authorized,
-        }
-    }
-
-    fn make_multisig_state(threshold: u8, members: Vec<[u8; 32]>) -> Vec<u8> {
-        let mut state = MultisigState::new([0u8; 32], threshold, members);
-        state.transaction_index = 1;
-        borsh::to_vec(&state).unwrap()
-    }
-
-    fn make_proposal(proposer: [u8; 32]) -> Vec<u8> {
-        let fake_program_id: ProgramId = [42u32; 8];
-        let proposal = Proposal::new(
-            1,
-            proposer,
-            [0u8; 32],
-            fake_program_id,
-            vec![0u32],
-            1,
-            vec![],
-            vec![],
-        );
-        borsh::to_vec(&proposal).unwrap()
-    }
+    use crate::test_utils::*;
+    use multisig_core::{MultisigState, ProposalStatus};
 
     #[test]
     fn test_reject_adds_rejection() {
         let members = vec![[1u8; 32], [2u8; 32], [3u8; 32]];
-        let state_data = make_multisig_state(2, members);
+        let state_data = make_state_with_proposal(2, members);
         let proposal_data = make_proposal([1u8; 32]);
 
         let accounts = vec![
-            make_account(&[10u8; 32], state_data, false),
+            make_account(&state_id(), state_data, false),
             make_account(&[2u8; 32], vec![], true),
-            make_account(&[20u8; 32], proposal_data, false),
+            make_account(&proposal_id(1), proposal_data, false),
         ];
 
         let (post_states, _) = handle(&accounts, 1);
 
-        let proposal: Proposal = borsh::from_slice(&Vec::from(post_states[2].account().data.clone())).unwrap();
+        let proposal = Proposal::from_bytes(&Vec::from(post_states[2].account().data.clone())).unwrap();
         assert_eq!(proposal.rejected.len(), 1);
         assert_eq!(proposal.approved.len(), 1); // proposer still approved
     }
@@ -124,18 +86,60 @@ mod tests {
     #[test]
     fn test_reject_auto_marks_dead_proposal() {
         let members = vec![[1u8; 32], [2u8; 32]];
-        let state_data = make_multisig_state(2, members);
+        let state_data = make_state_with_proposal(2, members);
         let proposal_data = make_proposal([1u8; 32]);
 
         let accounts = vec![
-            make_account(&[10u8; 32], state_data, false),
+            make_account(&state_id(), state_data, false),
+            make_account(&[2u8; 32], vec![], true),
+            make_account(&proposal_id(1), proposal_data, false),
+        ];
+
+        let (post_states, _) = handle(&accounts, 1);
+
+        let proposal = Proposal::from_bytes(&Vec::from(post_states[2].account().data.clone())).unwrap();
+        assert_eq!(proposal.status, ProposalStatus::Rejected);
+    }
+
+    #[test]
+    fn test_reject_ignores_rejections_of_removed_members() {
+        // Members 3, 4 and 5 rejected, then were removed: 2-of-2 remains
+        let state_data = make_state_with_proposal(2, vec![[1u8; 32], [2u8; 32]]);
+        let mut proposal = Proposal::from_bytes(&make_proposal([1u8; 32])).unwrap();
+        proposal.rejected = vec![[3u8; 32], [4u8; 32], [5u8; 32]];
+
+        let accounts = vec![
+            make_account(&state_id(), state_data, false),
+            make_account(&[2u8; 32], vec![], true),
+            make_account(&proposal_id(1), proposal.to_bytes(), false),
+        ];
+
+        let (post_states, _) = handle(&accounts, 1);
+
+        // Only member 2's rejection counts, which is enough to kill a 2-of-2 proposal
+        let proposal = Proposal::from_bytes(&Vec::from(post_states[2].account().data.clone())).unwrap();
+        assert_eq!(proposal.status, ProposalStatus::Rejected);
+    }
+
+    #[test]
+    fn test_reject_threshold_vetoes_reachable_proposal() {
+        // 3-of-7 where any 2 rejections kill a proposal
+        let members: Vec<[u8; 32]> = (1..=7u8).map(|i| [i; 32]).collect();
+        let mut state = MultisigState::new([0u8; 32], 3, members);
+        state.transaction_index = 1;
+        state.reject_threshold = Some(2);
+        let mut proposal = Proposal::from_bytes(&make_proposal([1u8; 32])).unwrap();
+        proposal.rejected = vec![[3u8; 32]];
+
+        let accounts = vec![
+            make_account(&state_id(), state.to_bytes(), false),
             make_account(&[2u8; 32], vec![], true),
-            make_account(&[20u8; 32], proposal_data, false),
+            make_account(&proposal_id(1), proposal.to_bytes(), false),
         ];
 
         let (post_states, _) = handle(&accounts, 1);
 
-        let proposal: Proposal = borsh::from_slice(&Vec::from(post_states[2].account().data.clone())).unwrap();
+        let proposal = Proposal::from_bytes(&Vec::from(post_states[2].account().data.clone())).unwrap();
         assert_eq!(proposal.status, ProposalStatus::Rejected);
     }
 }
@@ -0,0 +1,59 @@
+//! Fixtures shared by the handler tests.
+//!
+//! Accounts belong to the multisig with create key `[0; 32]` under the
+//! default (all-zero) program ID, so they pass the PDA and owner checks.
+
+use nssa_core::account::{Account, AccountId, AccountWithMetadata};
+use nssa_core::program::ProgramId;
+use multisig_core::{MultisigState, Proposal, compute_multisig_state_pda, compute_proposal_pda};
+
+pub fn make_account(id: &[u8; 32], data: Vec<u8>, authorized: bool) -> AccountWithMetadata {
+    let mut account = Account::default();
+    account.data = data.try_into().unwrap();
+    AccountWithMetadata {
+        account_id: AccountId::new(*id),
+        account,
+        is_authorized: authorized,
+    }
+}
+
+pub fn state_id() -> [u8; 32] {
+    *compute_multisig_state_pda(&Account::default().program_owner, &[0u8; 32]).value()
+}
+
+pub fn proposal_id(index: u64) -> [u8; 32] {
+    *compute_proposal_pda(&Account::default().program_owner, &[0u8; 32], index).value()
+}
+
+/// A multisig with no proposals yet.
+pub fn make_state(threshold: u8, members: Vec<[u8; 32]>) -> Vec<u8> {
+    MultisigState::new([0u8; 32], threshold, members).to_bytes()
+}
+
+/// A multisig whose proposal #1 exists.
+pub fn make_state_with_proposal(threshold: u8, members: Vec<[u8; 32]>) -> Vec<u8> {
+    let mut state = MultisigState::new([0u8; 32], threshold, members);
+    state.transaction_index = 1;
+    state.to_bytes()
+}
+
+/// Proposal #1: call program `[42; 8]` on account `[30; 32]`, authorized.
+pub fn new_proposal(proposer: [u8; 32]) -> Proposal {
+    let fake_program_id: ProgramId = [42u32; 8];
+    Proposal::new(
+        1,
+        proposer,
+        [0u8; 32],
+        fake_program_id,
+        vec![0u32],
+        vec![[30u8; 32]],
+        vec![],
+        vec![0u8],
+        String::new(),
+        None,
+    )
+}
+
+pub fn make_proposal(proposer: [u8; 32]) -> Vec<u8> {
+    new_proposal(proposer).to_bytes()
+}